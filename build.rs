@@ -0,0 +1,303 @@
+extern crate cc;
+extern crate pkg_config;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+struct OpenCV {
+  version: (u32, u32),
+  include_dirs: Vec<PathBuf>,
+  lib_dirs: Vec<PathBuf>,
+  libs: Vec<String>,
+}
+
+// Where a C struct is declared, which decides whether its layout can be
+// checked against the installed headers.
+#[derive(Clone, Copy, PartialEq)]
+enum Header {
+  Core,
+  Imgproc,
+  Font,
+  Persistence,
+  Internal,
+}
+
+struct Layout {
+  name: &'static str,
+  header: Header,
+  size64: usize,
+  align64: usize,
+  size32: Option<usize>,
+}
+
+const fn layout(name: &'static str, header: Header, size64: usize, align64: usize, size32: Option<usize>) -> Layout {
+  Layout { name: name, header: header, size64: size64, align64: align64, size32: size32 }
+}
+
+// The layouts src/ffi/types.rs mirrors. Both the Rust structs and the C
+// declarations are asserted against these numbers.
+const LAYOUTS: &[Layout] = &[
+  layout("CvAttrList", Header::Persistence, 16, 8, None),
+  layout("CvBox2D", Header::Core, 20, 4, Some(20)),
+  layout("CvConnectedComp", Header::Imgproc, 64, 8, None),
+  layout("CvContour", Header::Core, 128, 8, Some(88)),
+  layout("CvConvexityDefect", Header::Imgproc, 32, 8, Some(16)),
+  layout("CvFileMapNode", Header::Internal, 48, 8, None),
+  layout("CvFileNode", Header::Persistence, 32, 8, None),
+  layout("CvFont", Header::Font, 96, 8, None),
+  layout("CvHuMoments", Header::Imgproc, 56, 8, None),
+  layout("CvMat", Header::Core, 40, 8, Some(28)),
+  layout("CvMemBlock", Header::Core, 16, 8, None),
+  layout("CvMemStorage", Header::Core, 40, 8, Some(24)),
+  layout("CvMoments", Header::Imgproc, 144, 8, Some(144)),
+  layout("CvPoint", Header::Core, 8, 4, None),
+  layout("CvPoint2D32f", Header::Core, 8, 4, None),
+  layout("CvRect", Header::Core, 16, 4, None),
+  layout("CvScalar", Header::Core, 32, 8, None),
+  layout("CvSeq", Header::Core, 96, 8, Some(56)),
+  layout("CvSeqBlock", Header::Core, 32, 8, Some(20)),
+  layout("CvSize", Header::Core, 8, 4, None),
+  layout("CvSize2D32f", Header::Core, 8, 4, None),
+  layout("CvSlice", Header::Core, 8, 4, None),
+  layout("CvString", Header::Persistence, 16, 8, None),
+  layout("CvStringHashNode", Header::Persistence, 32, 8, None),
+  layout("CvTermCriteria", Header::Core, 16, 8, None),
+  layout("IplImage", Header::Core, 144, 8, Some(112)),
+  layout("IplROI", Header::Core, 20, 4, None),
+];
+
+fn feature(name: &str) -> bool {
+  env::var_os(format!("CARGO_FEATURE_{}", name.to_uppercase())).is_some()
+}
+
+// Before 3.0, image codecs and video capture both live in highgui.
+fn required_modules(version: (u32, u32)) -> Vec<&'static str> {
+  let legacy = version.0 < 3;
+  let mut modules = vec!["core", if legacy { "highgui" } else { "imgcodecs" }];
+  let features = [
+    ("imgproc", "imgproc"),
+    ("highgui", "highgui"),
+    ("objdetect", "objdetect"),
+    ("video", if legacy { "highgui" } else { "videoio" }),
+    ("calib3d", "calib3d"),
+    ("features2d", "features2d"),
+  ];
+  for &(name, module) in &features {
+    if feature(name) && !modules.contains(&module) {
+      modules.push(module);
+    }
+  }
+  modules
+}
+
+fn fail(message: &str) -> ! {
+  eprintln!("error: {}", message);
+  process::exit(1);
+}
+
+fn define(header: &str, name: &str) -> Option<u32> {
+  header.lines()
+    .filter_map(|line| {
+      let mut words = line.split_whitespace();
+      match (words.next(), words.next(), words.next()) {
+        (Some("#define"), Some(n), Some(value)) if n == name => value.parse().ok(),
+        _ => None,
+      }
+    })
+    .next()
+}
+
+fn header_version(include_dir: &Path) -> Option<(u32, u32)> {
+  let header = ["opencv2/core/version.hpp", "opencv4/opencv2/core/version.hpp"].iter()
+    .filter_map(|p| fs::read_to_string(include_dir.join(p)).ok())
+    .next()?;
+  match define(&header, "CV_VERSION_EPOCH") {
+    Some(epoch) => Some((epoch, define(&header, "CV_VERSION_MAJOR")?)),
+    None => match define(&header, "CV_VERSION_MAJOR") {
+      Some(major) => Some((major, define(&header, "CV_VERSION_MINOR")?)),
+      None => Some((define(&header, "CV_MAJOR_VERSION")?, define(&header, "CV_MINOR_VERSION")?)),
+    },
+  }
+}
+
+// `libopencv_core.so.2.4` links as `opencv_core`, `opencv_core2413.lib` as `opencv_core2413`.
+fn library_name(file_name: &str) -> Option<String> {
+  let name = file_name.trim_start_matches("lib").split('.').next().unwrap();
+  if name.starts_with("opencv_") { Some(name.to_string()) } else { None }
+}
+
+// Windows builds append the version, as in opencv_core249 or opencv_core4100;
+// any other name has to match the module exactly.
+fn find_library<'a>(libs: &'a [String], module: &str, version: (u32, u32)) -> Option<&'a String> {
+  let name = format!("opencv_{}", module);
+  let prefix = format!("{}{}", version.0, version.1);
+  libs.iter().find(|l| match l.strip_prefix(name.as_str()) {
+    Some("") => true,
+    Some(suffix) => suffix.starts_with(&prefix) && suffix.chars().all(|c| c.is_ascii_digit()),
+    None => false,
+  })
+}
+
+fn from_dir(dir: &Path) -> OpenCV {
+  let include_dir = dir.join("include");
+  let lib_dir = dir.join("lib");
+  let version = header_version(&include_dir).unwrap_or_else(|| fail(&format!(
+    "OPENCV_DIR is set to {} but no opencv2/core/version.hpp was found under {}",
+    dir.display(), include_dir.display())));
+  let entries = fs::read_dir(&lib_dir).unwrap_or_else(|e| fail(&format!(
+    "OPENCV_DIR is set to {} but {} cannot be read: {}", dir.display(), lib_dir.display(), e)));
+
+  let mut libs = entries
+    .filter_map(|e| e.ok())
+    .filter_map(|e| e.file_name().to_str().and_then(library_name))
+    .collect::<Vec<_>>();
+  libs.sort();
+  libs.dedup();
+  let include_dirs = vec![include_dir.join("opencv4"), include_dir];
+  OpenCV { version: version, include_dirs: include_dirs, lib_dirs: vec![lib_dir], libs: libs }
+}
+
+fn from_pkg_config() -> OpenCV {
+  let mut errors = Vec::new();
+  for name in &["opencv", "opencv4"] {
+    match pkg_config::Config::new().cargo_metadata(false).probe(name) {
+      Ok(library) => {
+        let mut numbers = library.version.split('.').map(|n| n.parse::<u32>().unwrap_or(0));
+        let version = (numbers.next().unwrap_or(0), numbers.next().unwrap_or(0));
+        return OpenCV {
+          version: version,
+          include_dirs: library.include_paths,
+          lib_dirs: library.link_paths,
+          libs: library.libs,
+        };
+      },
+      Err(e) => errors.push(e.to_string()),
+    }
+  }
+  fail(&format!(
+    "could not find OpenCV through pkg-config. Install the OpenCV development package, \
+     or set OPENCV_DIR to a directory containing include/ and lib/.\n{}", errors.join("\n")))
+}
+
+// Writes the layout asserts for src/ffi/types.rs and a C++ file repeating
+// them against the headers, so a struct that differs in the installed
+// OpenCV fails the build instead of corrupting memory at run time.
+fn check_layouts(opencv: &OpenCV) -> PathBuf {
+  let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+  let wide = env::var("CARGO_CFG_TARGET_POINTER_WIDTH").map_or(true, |w| w == "64");
+  let checked = |l: &&Layout| wide || l.size32.is_some();
+  let size = |l: &Layout| if wide { l.size64 } else { l.size32.unwrap() };
+
+  let mut rust = String::from("const _: () = {\n  use std::mem::{align_of, size_of};\n");
+  for l in LAYOUTS.iter().filter(checked) {
+    rust += &if wide {
+      format!("  assert!(size_of::<{0}>() == {1} && align_of::<{0}>() == {2});\n", l.name, l.size64, l.align64)
+    } else {
+      format!("  assert!(size_of::<{}>() == {});\n", l.name, size(l))
+    };
+  }
+  rust += "};\n";
+
+  let legacy = opencv.version.0 < 3;
+  let mut cpp = String::from("#include <opencv2/core/core_c.h>\n");
+  if feature("imgproc") {
+    cpp += "#include <opencv2/imgproc/imgproc_c.h>\n";
+  }
+  let declared = |l: &&Layout| match l.header {
+    Header::Core => true,
+    Header::Imgproc => feature("imgproc"),
+    Header::Font => legacy || feature("imgproc"),
+    Header::Persistence => opencv.version.0 < 4,
+    Header::Internal => false,
+  };
+  for l in LAYOUTS.iter().filter(checked).filter(declared) {
+    cpp += &format!("static_assert(sizeof({0}) == {1}, \"{0} is not {1} bytes as src/ffi/types.rs expects\");\n", l.name, size(l));
+    if wide {
+      cpp += &format!("static_assert(alignof({0}) == {1}, \"{0} is not {1}-byte aligned as src/ffi/types.rs expects\");\n", l.name, l.align64);
+    }
+  }
+
+  fs::write(out_dir.join("layout.rs"), rust).unwrap_or_else(|e| fail(&format!("cannot write layout.rs: {}", e)));
+  let cpp_path = out_dir.join("layout.cpp");
+  fs::write(&cpp_path, cpp).unwrap_or_else(|e| fail(&format!("cannot write layout.cpp: {}", e)));
+  cpp_path
+}
+
+// The core shim catches native exceptions on every version. OpenCV 4 also
+// dropped the C API for codecs, cascades and video; the rest of the shim
+// re-exports those entry points on top of the C++ classes.
+fn build_shim(opencv: &OpenCV, layout: &Path) {
+  let modern = opencv.version.0 >= 4;
+  let sources = fs::read_dir("src/shim").unwrap_or_else(|e| fail(&format!("src/shim cannot be read: {}", e)))
+    .filter_map(|e| e.ok())
+    .map(|e| e.path())
+    .filter(|p| match p.file_stem().and_then(|s| s.to_str()) {
+      Some("core") => true,
+      Some("features2d") => feature("features2d"),
+      Some("objdetect") => modern && feature("objdetect"),
+      Some("videoio") => modern && feature("video"),
+      _ => modern,
+    })
+    .collect::<Vec<_>>();
+  for source in &sources {
+    println!("cargo:rerun-if-changed={}", source.display());
+  }
+
+  cc::Build::new()
+    .cpp(true)
+    .flag_if_supported("-std=c++11")
+    .includes(&opencv.include_dirs)
+    .files(sources.iter().filter(|p| p.extension().map_or(false, |e| e == "cpp")))
+    .file(layout)
+    .compile("opencv_shim");
+}
+
+fn main() {
+  println!("cargo:rerun-if-changed=build.rs");
+  println!("cargo:rerun-if-env-changed=OPENCV_DIR");
+  println!("cargo:rerun-if-env-changed=PKG_CONFIG_PATH");
+  println!("cargo:rustc-check-cfg=cfg(opencv_2_4)");
+  println!("cargo:rustc-check-cfg=cfg(opencv_3_x)");
+  println!("cargo:rustc-check-cfg=cfg(opencv_4)");
+
+  let opencv = match env::var_os("OPENCV_DIR") {
+    Some(dir) => from_dir(Path::new(&dir)),
+    None => from_pkg_config(),
+  };
+
+  match opencv.version {
+    (2, 4) => println!("cargo:rustc-cfg=opencv_2_4"),
+    (3, _) => println!("cargo:rustc-cfg=opencv_3_x"),
+    (4, _) => println!("cargo:rustc-cfg=opencv_4"),
+    (major, minor) => fail(&format!("OpenCV {}.{} is not supported; use 2.4, 3.x or 4.x", major, minor)),
+  }
+  if opencv.version.0 >= 4 {
+    println!("cargo:warning=OpenCV {}.{} has no C FileStorage API, so opencv::persistence is not built",
+      opencv.version.0, opencv.version.1);
+  }
+
+  let mut libs = Vec::new();
+  let mut missing = Vec::new();
+  for module in required_modules(opencv.version) {
+    match find_library(&opencv.libs, module, opencv.version) {
+      Some(lib) => libs.push(lib),
+      None => missing.push(format!("opencv_{}", module)),
+    }
+  }
+  if !missing.is_empty() {
+    fail(&format!("OpenCV {}.{} was found but is missing required modules: {}",
+      opencv.version.0, opencv.version.1, missing.join(", ")));
+  }
+
+  let layout = check_layouts(&opencv);
+  build_shim(&opencv, &layout);
+
+  for dir in &opencv.lib_dirs {
+    println!("cargo:rustc-link-search=native={}", dir.display());
+  }
+  for lib in libs {
+    println!("cargo:rustc-link-lib={}", lib);
+  }
+}
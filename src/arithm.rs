@@ -0,0 +1,288 @@
+use std::convert::TryFrom;
+use std::ops::{Add, Sub};
+use std::ptr;
+use ffi::core::*;
+use ffi::types::{CvArr, CvScalar};
+use core::Scalar;
+use error::{Result, guard};
+use image::{Image, Mask};
+
+pub(crate) fn arr(image: &Image) -> *const CvArr {
+  image.as_raw() as *const CvArr
+}
+
+pub(crate) fn mask_arr(mask: Option<&Mask>) -> *const CvArr {
+  mask.map_or(ptr::null(), |m| arr(m))
+}
+
+impl Image {
+  // Runs an operation the C API cannot mask, then keeps only the masked pixels.
+  fn masked<F: FnOnce(*const CvArr)>(&self, mask: Option<&Mask>, f: F) -> Result<Image> {
+    match mask {
+      None => self.unary(|_, dst| f(dst)),
+      Some(mask) => {
+        let unmasked = self.unary(|_, dst| f(dst))?;
+        unmasked.unary(|src, dst| unsafe { cvCopy(src, dst, arr(mask)) })
+      },
+    }
+  }
+
+  fn binary_op(&self, other: &Image, mask: Option<&Mask>, func: &str) -> Result<()> {
+    self.expect_same_shape(other, func)?;
+    self.expect_mask(mask, func)
+  }
+
+  pub fn add(&self, other: &Image, mask: Option<&Mask>) -> Result<Image> {
+    self.binary(other, mask, "cvAdd", |src1, src2, dst| unsafe { cvAdd(src1, src2, dst, mask_arr(mask)) })
+  }
+
+  pub fn sub(&self, other: &Image, mask: Option<&Mask>) -> Result<Image> {
+    self.binary(other, mask, "cvSub", |src1, src2, dst| unsafe { cvSub(src1, src2, dst, mask_arr(mask)) })
+  }
+
+  pub fn mul(&self, other: &Image, scale: f64, mask: Option<&Mask>) -> Result<Image> {
+    self.binary_op(other, mask, "cvMul")?;
+    self.masked(mask, |dst| unsafe { cvMul(arr(self), arr(other), dst, scale) })
+  }
+
+  pub fn div(&self, other: &Image, scale: f64, mask: Option<&Mask>) -> Result<Image> {
+    self.binary_op(other, mask, "cvDiv")?;
+    self.masked(mask, |dst| unsafe { cvDiv(arr(self), arr(other), dst, scale) })
+  }
+
+  pub fn abs_diff(&self, other: &Image, mask: Option<&Mask>) -> Result<Image> {
+    self.binary_op(other, mask, "cvAbsDiff")?;
+    self.masked(mask, |dst| unsafe { cvAbsDiff(arr(self), arr(other), dst) })
+  }
+
+  pub fn add_weighted(&self, alpha: f64, other: &Image, beta: f64, gamma: f64, mask: Option<&Mask>) -> Result<Image> {
+    self.binary_op(other, mask, "cvAddWeighted")?;
+    self.masked(mask, |dst| unsafe { cvAddWeighted(arr(self), alpha, arr(other), beta, gamma, dst) })
+  }
+
+  pub fn add_scalar(&self, value: &Scalar, mask: Option<&Mask>) -> Result<Image> {
+    self.expect_mask(mask, "cvAddS")?;
+    self.unary(|src, dst| unsafe { cvAddS(src, CvScalar { val: *value }, dst, mask_arr(mask)) })
+  }
+
+  pub fn sub_scalar(&self, value: &Scalar, mask: Option<&Mask>) -> Result<Image> {
+    self.expect_mask(mask, "cvSubS")?;
+    let negated = [-value[0], -value[1], -value[2], -value[3]];
+    self.unary(|src, dst| unsafe { cvAddS(src, CvScalar { val: negated }, dst, mask_arr(mask)) })
+  }
+
+  pub fn sub_from_scalar(&self, value: &Scalar, mask: Option<&Mask>) -> Result<Image> {
+    self.expect_mask(mask, "cvSubRS")?;
+    self.unary(|src, dst| unsafe { cvSubRS(src, CvScalar { val: *value }, dst, mask_arr(mask)) })
+  }
+
+  pub fn abs_diff_scalar(&self, value: &Scalar, mask: Option<&Mask>) -> Result<Image> {
+    self.expect_mask(mask, "cvAbsDiffS")?;
+    self.masked(mask, |dst| unsafe { cvAbsDiffS(arr(self), dst, CvScalar { val: *value }) })
+  }
+
+  pub fn and(&self, other: &Image, mask: Option<&Mask>) -> Result<Image> {
+    self.binary(other, mask, "cvAnd", |src1, src2, dst| unsafe { cvAnd(src1, src2, dst, mask_arr(mask)) })
+  }
+
+  pub fn or(&self, other: &Image, mask: Option<&Mask>) -> Result<Image> {
+    self.binary(other, mask, "cvOr", |src1, src2, dst| unsafe { cvOr(src1, src2, dst, mask_arr(mask)) })
+  }
+
+  pub fn xor(&self, other: &Image, mask: Option<&Mask>) -> Result<Image> {
+    self.binary(other, mask, "cvXor", |src1, src2, dst| unsafe { cvXor(src1, src2, dst, mask_arr(mask)) })
+  }
+
+  pub fn not(&self) -> Result<Image> {
+    self.unary(|src, dst| unsafe { cvNot(src, dst) })
+  }
+
+  pub fn and_scalar(&self, value: &Scalar, mask: Option<&Mask>) -> Result<Image> {
+    self.expect_mask(mask, "cvAndS")?;
+    self.unary(|src, dst| unsafe { cvAndS(src, CvScalar { val: *value }, dst, mask_arr(mask)) })
+  }
+
+  pub fn or_scalar(&self, value: &Scalar, mask: Option<&Mask>) -> Result<Image> {
+    self.expect_mask(mask, "cvOrS")?;
+    self.unary(|src, dst| unsafe { cvOrS(src, CvScalar { val: *value }, dst, mask_arr(mask)) })
+  }
+
+  pub fn xor_scalar(&self, value: &Scalar, mask: Option<&Mask>) -> Result<Image> {
+    self.expect_mask(mask, "cvXorS")?;
+    self.unary(|src, dst| unsafe { cvXorS(src, CvScalar { val: *value }, dst, mask_arr(mask)) })
+  }
+
+  // Selects pixels whose every channel lies in [lower, upper].
+  pub fn in_range(&self, lower: &Scalar, upper: &Scalar) -> Result<Mask> {
+    let dst = Mask::new(&self.size())?;
+    guard(|| unsafe { cvInRangeS(arr(self), CvScalar { val: *lower }, CvScalar { val: *upper }, arr(&dst)) })?;
+    Ok(dst)
+  }
+}
+
+impl Mask {
+  pub fn and(&self, other: &Mask) -> Result<Mask> {
+    Image::and(self, other, None).and_then(Mask::try_from)
+  }
+
+  pub fn or(&self, other: &Mask) -> Result<Mask> {
+    Image::or(self, other, None).and_then(Mask::try_from)
+  }
+
+  pub fn not(&self) -> Result<Mask> {
+    Image::not(self).and_then(Mask::try_from)
+  }
+}
+
+impl<'a, 'b> Add<&'b Image> for &'a Image {
+  type Output = Image;
+  fn add(self, other: &'b Image) -> Image {
+    Image::add(self, other, None).unwrap_or_else(|e| panic!("cannot add images: {}", e))
+  }
+}
+
+impl<'a, 'b> Sub<&'b Image> for &'a Image {
+  type Output = Image;
+  fn sub(self, other: &'b Image) -> Image {
+    Image::sub(self, other, None).unwrap_or_else(|e| panic!("cannot subtract images: {}", e))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::convert::TryFrom;
+  use image::Mask;
+  use image::tests::{image_u8, samples_u8};
+
+  fn left_column_mask() -> Mask {
+    Mask::try_from(image_u8(3, 1, &[255, 0, 0, 255, 0, 0])).unwrap()
+  }
+
+  #[test]
+  fn add_saturates() {
+    let a = image_u8(3, 1, &[200, 100, 0, 255, 1, 2]);
+    let b = image_u8(3, 1, &[100, 100, 0, 1, 1, 250]);
+    assert_eq!(samples_u8(&a.add(&b, None).unwrap()), vec![255, 200, 0, 255, 2, 252]);
+    assert_eq!(samples_u8(&(&a + &b)), vec![255, 200, 0, 255, 2, 252]);
+  }
+
+  #[test]
+  fn sub_saturates() {
+    let a = image_u8(3, 1, &[10, 100, 0, 255, 1, 2]);
+    let b = image_u8(3, 1, &[20, 40, 1, 255, 0, 250]);
+    assert_eq!(samples_u8(&a.sub(&b, None).unwrap()), vec![0, 60, 0, 0, 1, 0]);
+    assert_eq!(samples_u8(&(&a - &b)), vec![0, 60, 0, 0, 1, 0]);
+  }
+
+  #[test]
+  fn mul_and_div() {
+    let a = image_u8(3, 1, &[20, 100, 3]);
+    let b = image_u8(3, 1, &[20, 4, 0]);
+    assert_eq!(samples_u8(&a.mul(&b, 1.0, None).unwrap()), vec![255, 255, 0]);
+    assert_eq!(samples_u8(&a.mul(&b, 0.1, None).unwrap()), vec![40, 40, 0]);
+    // Division by zero yields zero rather than saturating.
+    assert_eq!(samples_u8(&a.div(&b, 1.0, None).unwrap()), vec![1, 25, 0]);
+  }
+
+  #[test]
+  fn abs_diff_and_add_weighted() {
+    let a = image_u8(3, 1, &[10, 200, 255]);
+    let b = image_u8(3, 1, &[20, 100, 0]);
+    assert_eq!(samples_u8(&a.abs_diff(&b, None).unwrap()), vec![10, 100, 255]);
+    assert_eq!(samples_u8(&a.add_weighted(0.5, &b, 0.5, 0.0, None).unwrap()), vec![15, 150, 128]);
+    assert_eq!(samples_u8(&a.add_weighted(2.0, &b, 1.0, -10.0, None).unwrap()), vec![30, 255, 255]);
+  }
+
+  #[test]
+  fn scalar_variants_saturate() {
+    let a = image_u8(3, 1, &[250, 5, 100]);
+    assert_eq!(samples_u8(&a.add_scalar(&[10.0;4], None).unwrap()), vec![255, 15, 110]);
+    assert_eq!(samples_u8(&a.sub_scalar(&[10.0;4], None).unwrap()), vec![240, 0, 90]);
+    assert_eq!(samples_u8(&a.sub_from_scalar(&[30.0;4], None).unwrap()), vec![0, 25, 0]);
+    assert_eq!(samples_u8(&a.abs_diff_scalar(&[100.0;4], None).unwrap()), vec![150, 95, 0]);
+  }
+
+  #[test]
+  fn scalars_apply_per_channel() {
+    let a = image_u8(1, 3, &[10, 20, 30]);
+    assert_eq!(samples_u8(&a.add_scalar(&[1.0, 2.0, 3.0, 0.0], None).unwrap()), vec![11, 22, 33]);
+  }
+
+  #[test]
+  fn masked_ops_leave_unmasked_pixels_untouched() {
+    let a = image_u8(3, 1, &[200, 200, 200, 10, 10, 10]);
+    let b = image_u8(3, 1, &[100, 100, 100, 20, 20, 20]);
+    let mask = left_column_mask();
+    // cvAdd takes the mask natively; cvMul goes through `masked`.
+    assert_eq!(samples_u8(&a.add(&b, Some(&mask)).unwrap()), vec![255, 0, 0, 30, 0, 0]);
+    assert_eq!(samples_u8(&a.mul(&b, 1.0, Some(&mask)).unwrap()), vec![255, 0, 0, 200, 0, 0]);
+    assert_eq!(samples_u8(&a.abs_diff(&b, Some(&mask)).unwrap()), vec![100, 0, 0, 10, 0, 0]);
+    assert_eq!(samples_u8(&a.abs_diff_scalar(&[0.0;4], Some(&mask)).unwrap()), vec![200, 0, 0, 10, 0, 0]);
+  }
+
+  #[test]
+  fn mismatched_shapes_are_rejected() {
+    let a = image_u8(3, 1, &[1, 2, 3]);
+    let b = image_u8(2, 1, &[1, 2]);
+    assert!(a.add(&b, None).is_err());
+    assert!(a.mul(&b, 1.0, None).is_err());
+    let mask = left_column_mask();
+    assert!(a.add_scalar(&[1.0;4], Some(&mask)).is_err());
+  }
+
+  #[test]
+  #[should_panic(expected = "cannot add images")]
+  fn add_operator_panics_on_mismatch() {
+    let _ = &image_u8(3, 1, &[1, 2, 3]) + &image_u8(2, 1, &[1, 2]);
+  }
+
+  #[test]
+  fn bitwise_ops() {
+    let a = image_u8(3, 1, &[0b1100, 0xff, 0]);
+    let b = image_u8(3, 1, &[0b1010, 0x0f, 0]);
+    assert_eq!(samples_u8(&a.and(&b, None).unwrap()), vec![0b1000, 0x0f, 0]);
+    assert_eq!(samples_u8(&a.or(&b, None).unwrap()), vec![0b1110, 0xff, 0]);
+    assert_eq!(samples_u8(&a.xor(&b, None).unwrap()), vec![0b0110, 0xf0, 0]);
+    assert_eq!(samples_u8(&a.not().unwrap()), vec![0b1111_0011, 0, 0xff]);
+  }
+
+  #[test]
+  fn bitwise_scalar_variants() {
+    let a = image_u8(3, 1, &[0b1100, 0xff, 0]);
+    assert_eq!(samples_u8(&a.and_scalar(&[6.0;4], None).unwrap()), vec![0b0100, 6, 0]);
+    assert_eq!(samples_u8(&a.or_scalar(&[6.0;4], None).unwrap()), vec![0b1110, 0xff, 6]);
+    assert_eq!(samples_u8(&a.xor_scalar(&[6.0;4], None).unwrap()), vec![0b1010, 0xf9, 6]);
+  }
+
+  #[test]
+  fn masked_bitwise_ops_leave_unmasked_pixels_untouched() {
+    let a = image_u8(3, 1, &[0xff, 0xff, 0xff, 0x0f, 0x0f, 0x0f]);
+    let b = image_u8(3, 1, &[0xf0, 0xf0, 0xf0, 0xf0, 0xf0, 0xf0]);
+    let mask = left_column_mask();
+    assert_eq!(samples_u8(&a.and(&b, Some(&mask)).unwrap()), vec![0xf0, 0, 0, 0, 0, 0]);
+    assert_eq!(samples_u8(&a.xor_scalar(&[1.0;4], Some(&mask)).unwrap()), vec![0xfe, 0, 0, 0x0e, 0, 0]);
+  }
+
+  #[test]
+  fn in_range_includes_both_bounds() {
+    let a = image_u8(5, 1, &[9, 10, 15, 20, 21]);
+    let range = a.in_range(&[10.0;4], &[20.0;4]).unwrap();
+    assert_eq!(samples_u8(&range), vec![0, 255, 255, 255, 0]);
+  }
+
+  #[test]
+  fn in_range_needs_every_channel() {
+    let a = image_u8(2, 2, &[10, 10, 10, 30]);
+    let range = a.in_range(&[10.0, 10.0, 0.0, 0.0], &[20.0, 20.0, 0.0, 0.0]).unwrap();
+    assert_eq!(samples_u8(&range), vec![255, 0]);
+  }
+
+  #[test]
+  fn mask_ops() {
+    let left = left_column_mask();
+    let top = Mask::try_from(image_u8(3, 1, &[255, 255, 255, 0, 0, 0])).unwrap();
+    assert_eq!(samples_u8(&left.and(&top).unwrap()), vec![255, 0, 0, 0, 0, 0]);
+    assert_eq!(samples_u8(&left.or(&top).unwrap()), vec![255, 255, 255, 255, 0, 0]);
+    assert_eq!(samples_u8(&left.not().unwrap()), vec![0, 255, 255, 0, 255, 255]);
+  }
+}
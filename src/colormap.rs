@@ -0,0 +1,103 @@
+use error::Result;
+use image::{ColorMap, Depth, Image};
+
+// Control points as (position, red, green, blue), all in [0, 1].
+type Stop = (f64, f64, f64, f64);
+
+fn hex(stops: &[u32]) -> Vec<Stop> {
+  let last = (stops.len() - 1) as f64;
+  stops.iter().enumerate().map(|(i, &c)| {
+    let channel = |shift: u32| ((c >> shift) & 0xff) as f64 / 255.0;
+    (i as f64 / last, channel(16), channel(8), channel(0))
+  }).collect()
+}
+
+fn stops(map: ColorMap) -> Vec<Stop> {
+  match map {
+    ColorMap::Autumn => vec![(0.0, 1.0, 0.0, 0.0), (1.0, 1.0, 1.0, 0.0)],
+    ColorMap::Bone => vec![(0.0, 0.0, 0.0, 0.0), (0.365, 0.319, 0.319, 0.444), (0.746, 0.651, 0.778, 0.778), (1.0, 1.0, 1.0, 1.0)],
+    ColorMap::Cool => vec![(0.0, 0.0, 1.0, 1.0), (1.0, 1.0, 0.0, 1.0)],
+    ColorMap::Hot => vec![(0.0, 0.0416, 0.0, 0.0), (0.365, 1.0, 0.0, 0.0), (0.746, 1.0, 1.0, 0.0), (1.0, 1.0, 1.0, 1.0)],
+    ColorMap::Hsv => vec![(0.0, 1.0, 0.0, 0.0), (1.0 / 6.0, 1.0, 1.0, 0.0), (2.0 / 6.0, 0.0, 1.0, 0.0), (3.0 / 6.0, 0.0, 1.0, 1.0),
+      (4.0 / 6.0, 0.0, 0.0, 1.0), (5.0 / 6.0, 1.0, 0.0, 1.0), (1.0, 1.0, 0.0, 0.0)],
+    ColorMap::Inferno => hex(&[0x000004, 0x1b0c41, 0x4a0c6b, 0x781c6d, 0xa52c60, 0xcf4446, 0xed6925, 0xfb9b06, 0xf7d13d, 0xfcffa4]),
+    ColorMap::Jet => vec![(0.0, 0.0, 0.0, 0.5), (0.125, 0.0, 0.0, 1.0), (0.375, 0.0, 1.0, 1.0), (0.625, 1.0, 1.0, 0.0), (0.875, 1.0, 0.0, 0.0), (1.0, 0.5, 0.0, 0.0)],
+    ColorMap::Magma => hex(&[0x000004, 0x180f3d, 0x440f76, 0x721f81, 0x9e2f7f, 0xcd4071, 0xf1605d, 0xfd9668, 0xfeca8d, 0xfcfdbf]),
+    ColorMap::Ocean => vec![(0.0, 0.0, 0.5, 0.0), (1.0 / 3.0, 0.0, 0.0, 1.0 / 3.0), (2.0 / 3.0, 0.0, 0.5, 2.0 / 3.0), (1.0, 1.0, 1.0, 1.0)],
+    ColorMap::Rainbow => vec![(0.0, 0.5, 0.0, 1.0), (0.25, 0.0, 0.707, 0.924), (0.5, 0.5, 1.0, 0.707), (0.75, 1.0, 0.707, 0.383), (1.0, 1.0, 0.0, 0.0)],
+    ColorMap::Spring => vec![(0.0, 1.0, 0.0, 1.0), (1.0, 1.0, 1.0, 0.0)],
+    ColorMap::Summer => vec![(0.0, 0.0, 0.5, 0.4), (1.0, 1.0, 1.0, 0.4)],
+    ColorMap::Viridis => hex(&[0x440154, 0x482878, 0x3e4989, 0x31688e, 0x26828e, 0x1f9e89, 0x35b779, 0x6ece58, 0xb5de2b, 0xfde725]),
+    ColorMap::Winter => vec![(0.0, 0.0, 0.0, 1.0), (1.0, 0.0, 1.0, 0.5)],
+  }
+}
+
+// Interpolates the control points into blue, green and red tables, in the
+// channel order of the images we display.
+fn tables(map: ColorMap) -> [[u8;256];3] {
+  let stops = stops(map);
+  let bgr = |s: Stop| [s.3, s.2, s.1];
+  let mut tables = [[0u8;256];3];
+  for (channel, table) in tables.iter_mut().enumerate() {
+    for (i, entry) in table.iter_mut().enumerate() {
+      let x = i as f64 / 255.0;
+      let upper = stops.iter().position(|s| s.0 >= x).unwrap_or(stops.len() - 1).max(1);
+      let (a, b) = (stops[upper - 1], stops[upper]);
+      let t = if b.0 > a.0 { ((x - a.0) / (b.0 - a.0)).clamp(0.0, 1.0) } else { 0.0 };
+      let (from, to) = (bgr(a)[channel], bgr(b)[channel]);
+      *entry = ((from + (to - from) * t) * 255.0).round() as u8;
+    }
+  }
+  tables
+}
+
+impl Image {
+  // Renders a single-channel 8-bit image as a BGR heatmap; scale other
+  // depths into 0..255 first, e.g. with `normalize`.
+  pub fn apply_color_map(&self, map: ColorMap) -> Result<Image> {
+    self.expect_depth(&[Depth::U8], "apply_color_map")?;
+    self.expect_channels(&[1], "apply_color_map")?;
+    Image::merge(&[self, self, self])?.lut_per_channel(&tables(map))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use image::ColorMap;
+  use super::tables;
+
+  fn bgr(tables: &[[u8;256];3], i: usize) -> [u8;3] {
+    [tables[0][i], tables[1][i], tables[2][i]]
+  }
+
+  #[test]
+  fn autumn_ramps_green_over_red() {
+    let autumn = tables(ColorMap::Autumn);
+    assert_eq!(bgr(&autumn, 0), [0, 0, 255]);
+    assert_eq!(bgr(&autumn, 128), [0, 128, 255]);
+    assert_eq!(bgr(&autumn, 255), [0, 255, 255]);
+    assert!((0..256).all(|i| autumn[1][i] as usize == i));
+  }
+
+  #[test]
+  fn jet_runs_from_dark_blue_to_dark_red() {
+    let jet = tables(ColorMap::Jet);
+    assert_eq!(bgr(&jet, 0), [128, 0, 0]);
+    assert_eq!(bgr(&jet, 255), [0, 0, 128]);
+    // Green peaks across the middle of the map.
+    assert_eq!(jet[1][128], 255);
+  }
+
+  #[test]
+  fn viridis_matches_the_reference_table() {
+    let viridis = tables(ColorMap::Viridis);
+    assert_eq!(bgr(&viridis, 0), [84, 1, 68]);
+    assert_eq!(bgr(&viridis, 255), [37, 231, 253]);
+    // Ten control points only approximate the 256-entry table, whose middle
+    // entry is #21918c.
+    let middle = bgr(&viridis, 128);
+    for (&actual, &expected) in middle.iter().zip(&[0x8c, 0x91, 0x21]) {
+      assert!((actual as i32 - expected).abs() <= 2, "{:?}", middle);
+    }
+  }
+}
@@ -0,0 +1,146 @@
+use std::mem;
+use std::ptr;
+use libc::{c_int, c_void};
+use ffi::core::*;
+use ffi::types::{CvArr, CvMat};
+use arithm::{arr, mask_arr};
+use core::Normalization;
+use error::{Error, Result, guard};
+use image::{Depth, Image, Mask};
+use mat::Mat;
+
+fn channel_arrs(channels: &[Option<&Image>]) -> [*const CvArr;4] {
+  let mut arrs = [ptr::null();4];
+  for (a, channel) in arrs.iter_mut().zip(channels) {
+    *a = channel.map_or(ptr::null(), arr);
+  }
+  arrs
+}
+
+impl Image {
+  pub fn split(&self) -> Result<Vec<Image>> {
+    let channels = (0..self.channels())
+      .map(|_| Image::new(&self.size(), self.depth()?, 1))
+      .collect::<Result<Vec<_>>>()?;
+    let dst = channel_arrs(&channels.iter().map(Some).collect::<Vec<_>>());
+    guard(|| unsafe { cvSplit(arr(self), dst[0], dst[1], dst[2], dst[3]) })?;
+    Ok(channels)
+  }
+
+  pub fn extract_channel(&self, index: u32) -> Result<Image> {
+    self.expect_channel_index(index)?;
+    Image::new_with(&self.size(), self.depth()?, 1, |channel| {
+      let mut dst = [ptr::null();4];
+      dst[index as usize] = channel;
+      unsafe { cvSplit(arr(self), dst[0], dst[1], dst[2], dst[3]) }
+    })
+  }
+
+  pub fn insert_channel(&mut self, channel: &Image, index: u32) -> Result<()> {
+    self.expect_channel_index(index)?;
+    Image::mix_channels(&[channel], &mut [self], &[(0, index)])
+  }
+
+  pub fn merge(channels: &[&Image]) -> Result<Image> {
+    let first = match channels.first() {
+      Some(first) if channels.len() <= 4 => first,
+      _ => return Err(Error::BadArgument(format!("cvMerge needs 1 to 4 channels, got {}", channels.len()))),
+    };
+    for channel in channels {
+      first.expect_same_shape(channel, "cvMerge")?;
+    }
+    if first.channels() != 1 {
+      return Err(Error::BadArgument(format!("cvMerge needs single-channel images, got {} channels", first.channels())));
+    }
+
+    let src = channel_arrs(&channels.iter().map(|c| Some(*c)).collect::<Vec<_>>());
+    Image::new_with(&first.size(), first.depth()?, channels.len() as u32, |dst| unsafe { cvMerge(src[0], src[1], src[2], src[3], dst) })
+  }
+
+  // Channels are numbered across all images of a side, so with a 3-channel
+  // and a 1-channel source, `3` names the single channel of the second.
+  pub fn mix_channels(src: &[&Image], dst: &mut [&mut Image], from_to: &[(u32, u32)]) -> Result<()> {
+    let size = match src.first() {
+      Some(first) => first.size(),
+      None => return Err(Error::BadArgument("cvMixChannels needs at least one source image".to_string())),
+    };
+    let depth = src[0].depth()?;
+    for other in src.iter().map(|i| &**i).chain(dst.iter().map(|i| &**i)) {
+      let other_depth = other.depth()?;
+      if other.size() != size || other_depth != depth {
+        return Err(Error::BadArgument(format!("cvMixChannels needs images of the same size and depth, got {}x{} {:?} and {}x{} {:?}",
+          size.width, size.height, depth, other.width(), other.height(), other_depth)));
+      }
+    }
+
+    let src_channels = src.iter().map(|i| i.channels()).sum::<u32>();
+    let dst_channels = dst.iter().map(|i| i.channels()).sum::<u32>();
+    if let Some(&(from, to)) = from_to.iter().find(|&&(from, to)| from >= src_channels || to >= dst_channels) {
+      return Err(Error::BadArgument(format!("channel pair ({}, {}) is out of range for {} source and {} destination channels",
+        from, to, src_channels, dst_channels)));
+    }
+
+    let src_arrs = src.iter().map(|i| arr(i)).collect::<Vec<_>>();
+    let dst_arrs = dst.iter().map(|i| arr(i)).collect::<Vec<_>>();
+    let pairs = from_to.iter().flat_map(|&(from, to)| vec![from as c_int, to as c_int]).collect::<Vec<_>>();
+    guard(|| unsafe {
+      cvMixChannels(src_arrs.as_ptr(), src_arrs.len() as c_int, dst_arrs.as_ptr(), dst_arrs.len() as c_int,
+        pairs.as_ptr(), from_to.len() as c_int);
+    })
+  }
+
+  pub fn coi(&self) -> Option<u32> {
+    match unsafe { cvGetImageCOI(self.as_raw()) } {
+      0 => None,
+      coi => Some(coi as u32 - 1),
+    }
+  }
+
+  pub fn set_coi(&mut self, index: Option<u32>) -> Result<()> {
+    if let Some(index) = index {
+      self.expect_channel_index(index)?;
+    }
+    let coi = index.map_or(0, |i| i as c_int + 1);
+    guard(|| unsafe { cvSetImageCOI(self.as_raw(), coi) })
+  }
+
+  // Computes `saturate(alpha * pixel + beta)` into an image of the given depth.
+  pub fn convert_to(&self, depth: Depth, alpha: f64, beta: f64) -> Result<Image> {
+    Image::new_with(&self.size(), depth, self.channels(), |dst| unsafe { cvConvertScale(arr(self), dst, alpha, beta) })
+  }
+
+  // Computes `saturate(|alpha * pixel + beta|)` into an 8-bit image.
+  pub fn convert_scale_abs(&self, alpha: f64, beta: f64) -> Result<Image> {
+    Image::new_with(&self.size(), Depth::U8, self.channels(), |dst| unsafe { cvConvertScaleAbs(arr(self), dst, alpha, beta) })
+  }
+
+  pub fn normalize(&self, normalization: Normalization, depth: Depth, mask: Option<&Mask>) -> Result<Image> {
+    self.expect_mask(mask, "cvNormalize")?;
+    let (a, b, norm_type) = match normalization {
+      Normalization::MinMax(low, high) => (low, high, 32), // CV_MINMAX
+      Normalization::Norm(norm_type, value) => (value, 0.0, norm_type as c_int),
+    };
+    Image::new_with(&self.size(), depth, self.channels(), |dst| unsafe { cvNormalize(arr(self), dst, a, b, norm_type, mask_arr(mask)) })
+  }
+
+  pub fn lut(&self, table: &[u8;256]) -> Result<Image> {
+    self.expect_depth(&[Depth::U8], "cvLUT")?;
+    let lut = Mat::from_slice(1, 256, table)?;
+    self.unary(|src, dst| unsafe { cvLUT(src, dst, lut.as_arr()) })
+  }
+
+  // Maps each channel through its own table, in channel order.
+  pub fn lut_per_channel(&self, tables: &[[u8;256]]) -> Result<Image> {
+    self.expect_depth(&[Depth::U8], "cvLUT")?;
+    if tables.len() != self.channels() as usize {
+      return Err(Error::BadArgument(format!("cvLUT needs one table per channel, got {} tables for {} channels", tables.len(), self.channels())));
+    }
+    let mut interleaved = (0..256).flat_map(|i| tables.iter().map(move |t| t[i])).collect::<Vec<u8>>();
+    let mut lut = unsafe { mem::zeroed::<CvMat>() };
+    self.unary(|src, dst| unsafe {
+      let lut_type = (tables.len() as c_int - 1) << 3; // CV_8UC(n)
+      cvInitMatHeader(&mut lut, 1, 256, lut_type, interleaved.as_mut_ptr() as *mut c_void, 0x7fffffff); // CV_AUTOSTEP
+      cvLUT(src, dst, &lut as *const CvMat as *const CvArr);
+    })
+  }
+}
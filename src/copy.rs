@@ -0,0 +1,72 @@
+use libc::c_int;
+use ffi::core::*;
+use arithm::arr;
+use core::Size;
+use error::{Error, Result, guard};
+use image::{FlipMode, Image, Rotation};
+
+fn flip_code(mode: FlipMode) -> c_int {
+  match mode {
+    FlipMode::Horizontal => 1, // around the y axis
+    FlipMode::Vertical => 0, // around the x axis
+    FlipMode::Both => -1,
+  }
+}
+
+impl Image {
+  pub fn flip(&self, mode: FlipMode) -> Result<Image> {
+    self.unary(|src, dst| unsafe { cvFlip(src, dst, flip_code(mode)) })
+  }
+
+  pub fn flip_in_place(&mut self, mode: FlipMode) -> Result<()> {
+    guard(|| unsafe { cvFlip(arr(self), arr(self), flip_code(mode)) })
+  }
+
+  pub fn transpose(&self) -> Result<Image> {
+    Image::new_with(&Size::new(self.height(), self.width()), self.depth()?, self.channels(), |dst| unsafe { cvTranspose(arr(self), dst) })
+  }
+
+  // Only square images transpose without a new buffer; others are replaced.
+  pub fn transpose_in_place(&mut self) -> Result<()> {
+    if self.width() == self.height() {
+      return guard(|| unsafe { cvTranspose(arr(self), arr(self)) });
+    }
+    *self = self.transpose()?;
+    Ok(())
+  }
+
+  pub fn rotate_90(&self, rotation: Rotation) -> Result<Image> {
+    match rotation {
+      Rotation::Clockwise90 => {
+        let mut dst = self.transpose()?;
+        dst.flip_in_place(FlipMode::Horizontal)?;
+        Ok(dst)
+      },
+      Rotation::CounterClockwise90 => {
+        let mut dst = self.transpose()?;
+        dst.flip_in_place(FlipMode::Vertical)?;
+        Ok(dst)
+      },
+      Rotation::Rotate180 => self.flip(FlipMode::Both),
+    }
+  }
+
+  pub fn rotate_90_in_place(&mut self, rotation: Rotation) -> Result<()> {
+    match rotation {
+      Rotation::Rotate180 => self.flip_in_place(FlipMode::Both),
+      _ => {
+        *self = self.rotate_90(rotation)?;
+        Ok(())
+      },
+    }
+  }
+
+  // Tiles the image `nx` times horizontally and `ny` times vertically.
+  pub fn repeat(&self, nx: u32, ny: u32) -> Result<Image> {
+    if nx == 0 || ny == 0 {
+      return Err(Error::BadArgument(format!("cvRepeat needs at least one tile in each direction, got {}x{}", nx, ny)));
+    }
+    let size = Size::new(self.width() * nx as i32, self.height() * ny as i32);
+    Image::new_with(&size, self.depth()?, self.channels(), |dst| unsafe { cvRepeat(arr(self), dst) })
+  }
+}
@@ -1,9 +1,21 @@
 use ffi::core::*;
-use ffi::types::{CvSeq, CvRect};
+#[cfg(feature = "imgproc")]
+use ffi::imgproc::*;
+use ffi::types::{CvConvexityDefect, CvMemStorage, CvPoint, CvPoint2D32f, CvSeq, CvRect, CvTermCriteria};
+#[cfg(feature = "imgproc")]
+use ffi::types::{CvArr, CvBox2D, CvSize2D32f};
+use error::{Error, Result, guard};
+use libc::c_schar;
 
 
+use std::convert::TryFrom;
 use std::path::Path;
 use std::ffi::CString;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Add, Mul, Sub};
+#[cfg(feature = "imgproc")]
+use std::ptr;
 
 pub fn as_c_str(path: &Path) -> CString {
     CString::new(path.as_os_str().to_str().unwrap()).unwrap()
@@ -31,7 +43,7 @@ impl Color {
   }
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Point {
   pub x: i32,
   pub y: i32,
@@ -43,7 +55,135 @@ impl Point {
   }
 }
 
-#[derive(Clone, PartialEq, Debug)]
+impl Add for Point {
+  type Output = Point;
+  fn add(self, other: Point) -> Point {
+    Point::new(self.x + other.x, self.y + other.y)
+  }
+}
+
+impl Sub for Point {
+  type Output = Point;
+  fn sub(self, other: Point) -> Point {
+    Point::new(self.x - other.x, self.y - other.y)
+  }
+}
+
+impl Mul<i32> for Point {
+  type Output = Point;
+  fn mul(self, factor: i32) -> Point {
+    Point::new(self.x * factor, self.y * factor)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Point2f {
+  pub x: f32,
+  pub y: f32,
+}
+
+impl Point2f {
+  pub fn new(x: f32, y: f32) -> Point2f {
+    Point2f { x: x, y: y }
+  }
+
+  pub fn round(&self) -> Point {
+    Point::new(self.x.round() as i32, self.y.round() as i32)
+  }
+
+  pub(crate) fn from_raw(point: &CvPoint2D32f) -> Point2f {
+    Point2f::new(point.x, point.y)
+  }
+
+  #[cfg(feature = "imgproc")]
+  pub(crate) fn to_raw(self) -> CvPoint2D32f {
+    CvPoint2D32f { x: self.x, y: self.y }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Point2d {
+  pub x: f64,
+  pub y: f64,
+}
+
+impl Point2d {
+  pub fn new(x: f64, y: f64) -> Point2d {
+    Point2d { x: x, y: y }
+  }
+
+  pub fn round(&self) -> Point {
+    Point::new(self.x.round() as i32, self.y.round() as i32)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Point3f {
+  pub x: f32,
+  pub y: f32,
+  pub z: f32,
+}
+
+impl Point3f {
+  pub fn new(x: f32, y: f32, z: f32) -> Point3f {
+    Point3f { x: x, y: y, z: z }
+  }
+}
+
+fn exact_f32(value: i32) -> Result<f32> {
+  match value as f32 {
+    f if f as f64 == value as f64 => Ok(f),
+    _ => Err(Error::BadArgument(format!("{} is not exactly representable as f32", value))),
+  }
+}
+
+fn exact_i32(value: f64) -> Result<i32> {
+  match value as i32 {
+    i if i as f64 == value => Ok(i),
+    _ => Err(Error::BadArgument(format!("{} is not exactly representable as i32", value))),
+  }
+}
+
+impl From<Point> for Point2d {
+  fn from(point: Point) -> Point2d {
+    Point2d::new(point.x as f64, point.y as f64)
+  }
+}
+
+impl From<Point2f> for Point2d {
+  fn from(point: Point2f) -> Point2d {
+    Point2d::new(point.x as f64, point.y as f64)
+  }
+}
+
+impl TryFrom<Point> for Point2f {
+  type Error = Error;
+  fn try_from(point: Point) -> Result<Point2f> {
+    Ok(Point2f::new(exact_f32(point.x)?, exact_f32(point.y)?))
+  }
+}
+
+impl TryFrom<Point2f> for Point {
+  type Error = Error;
+  fn try_from(point: Point2f) -> Result<Point> {
+    Ok(Point::new(exact_i32(point.x as f64)?, exact_i32(point.y as f64)?))
+  }
+}
+
+impl TryFrom<Point2d> for Point {
+  type Error = Error;
+  fn try_from(point: Point2d) -> Result<Point> {
+    Ok(Point::new(exact_i32(point.x)?, exact_i32(point.y)?))
+  }
+}
+
+// Edges of rects near the ends of the i32 range are computed in i64 and
+// clamped back.
+fn saturate(value: i64) -> i32 {
+  value.max(i32::MIN as i64).min(i32::MAX as i64) as i32
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Rect {
   pub x: i32,
   pub y: i32,
@@ -55,45 +195,361 @@ impl Rect {
   pub fn new(x: i32, y: i32, width: i32, height: i32) -> Rect {
     Rect { x: x, y: y, width: width, height: height }
   }
+
+  pub fn from_points(tl: &Point, br: &Point) -> Rect {
+    let (left, right) = if tl.x <= br.x { (tl.x, br.x) } else { (br.x, tl.x) };
+    let (top, bottom) = if tl.y <= br.y { (tl.y, br.y) } else { (br.y, tl.y) };
+    Rect::new(left, top, right.saturating_sub(left), bottom.saturating_sub(top))
+  }
+
+  pub fn tl(&self) -> Point {
+    Point::new(self.x, self.y)
+  }
+
+  pub fn br(&self) -> Point {
+    Point::new(saturate(self.right()), saturate(self.bottom()))
+  }
+
+  fn right(&self) -> i64 {
+    self.x as i64 + self.width as i64
+  }
+
+  fn bottom(&self) -> i64 {
+    self.y as i64 + self.height as i64
+  }
+
+  pub fn size(&self) -> Size {
+    Size::new(self.width, self.height)
+  }
+
+  pub fn center(&self) -> Point {
+    Point::new(saturate(self.x as i64 + self.width as i64 / 2), saturate(self.y as i64 + self.height as i64 / 2))
+  }
+
+  pub fn area(&self) -> i64 {
+    if self.is_empty() { 0 } else { self.width as i64 * self.height as i64 }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.width <= 0 || self.height <= 0
+  }
+
+  pub fn contains(&self, point: &Point) -> bool {
+    self.x <= point.x && (point.x as i64) < self.right() && self.y <= point.y && (point.y as i64) < self.bottom()
+  }
+
+  pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+    let left = self.x.max(other.x) as i64;
+    let top = self.y.max(other.y) as i64;
+    let right = self.right().min(other.right());
+    let bottom = self.bottom().min(other.bottom());
+    if left < right && top < bottom {
+      Some(Rect::new(left as i32, top as i32, saturate(right - left), saturate(bottom - top)))
+    } else {
+      None
+    }
+  }
+
+  #[cfg(feature = "imgproc")]
+  pub fn bounding_union(&self, other: &Rect) -> Result<Rect> {
+    let a = CvRect { x: self.x as i32, y: self.y as i32, width: self.width as i32, height: self.height as i32 };
+    let b = CvRect { x: other.x as i32, y: other.y as i32, width: other.width as i32, height: other.height as i32 };
+    let rect = guard(|| unsafe { cvMaxRect(&a, &b) })?;
+    Ok(Rect::new(rect.x as i32, rect.y as i32, rect.width as i32, rect.height as i32))
+  }
+
+  pub fn union_area(&self, other: &Rect) -> i64 {
+    self.area() + other.area() - self.intersection(other).map_or(0, |r| r.area())
+  }
+
+  pub fn iou(&self, other: &Rect) -> f64 {
+    match self.union_area(other) {
+      0 => 0.0,
+      union => self.intersection(other).map_or(0, |r| r.area()) as f64 / union as f64,
+    }
+  }
+
+  pub fn scale(&self, factor: f64) -> Rect {
+    let left = (self.x as f64 * factor).round() as i32;
+    let top = (self.y as f64 * factor).round() as i32;
+    let right = (self.right() as f64 * factor).round() as i32;
+    let bottom = (self.bottom() as f64 * factor).round() as i32;
+    Rect::new(left, top, right.saturating_sub(left), bottom.saturating_sub(top))
+  }
+
+  pub fn clamp(&self, size: &Size) -> Rect {
+    self.intersection(&Rect::new(0, 0, size.width, size.height))
+      .unwrap_or(Rect::new(self.x.max(0).min(size.width), self.y.max(0).min(size.height), 0, 0))
+  }
+}
+
+impl Add<Point> for Rect {
+  type Output = Rect;
+  fn add(self, offset: Point) -> Rect {
+    Rect::new(self.x + offset.x, self.y + offset.y, self.width, self.height)
+  }
+}
+
+impl Sub<Point> for Rect {
+  type Output = Rect;
+  fn sub(self, offset: Point) -> Rect {
+    Rect::new(self.x - offset.x, self.y - offset.y, self.width, self.height)
+  }
 }
 
 pub type Scalar = [f64;4];
 
-pub struct Seq {
-  pub raw: *mut CvSeq,
-  pub curr: usize,
+#[derive(Clone, PartialEq, Debug)]
+pub struct ConvexityDefect {
+  pub start: Point,
+  pub end: Point,
+  pub depth_point: Point,
+  pub depth: f32,
 }
 
-impl Iterator for Seq {
-  type Item = Rect;
-  fn next(&mut self) -> Option<Rect> {
-    unsafe {
-      if self.curr.lt(&self.len()) {
-        match cvGetSeqElem(&*self.raw, self.curr as i32) {
-          c if !c.is_null() => {
-            let ref rect = *(c as *mut CvRect);
-            self.curr += 1;
-            Some(Rect::new(rect.x as i32, rect.y as i32, rect.width as i32, rect.height as i32))
-          },
-          _ => None
-        }
-      } else {
-        None
-      }
+pub trait SeqElement: Sized {
+  fn elem_size() -> usize;
+  /// Reads one element out of a sequence block.
+  ///
+  /// # Safety
+  ///
+  /// `elem` must point to at least `elem_size()` readable bytes laid out as
+  /// the C type this element mirrors.
+  unsafe fn read(elem: *const c_schar) -> Self;
+}
+
+impl SeqElement for Point {
+  fn elem_size() -> usize { mem::size_of::<CvPoint>() }
+  unsafe fn read(elem: *const c_schar) -> Point {
+    let ref point = *(elem as *const CvPoint);
+    Point::new(point.x as i32, point.y as i32)
+  }
+}
+
+impl SeqElement for Point2f {
+  fn elem_size() -> usize { mem::size_of::<CvPoint2D32f>() }
+  unsafe fn read(elem: *const c_schar) -> Point2f {
+    Point2f::from_raw(&*(elem as *const CvPoint2D32f))
+  }
+}
+
+impl SeqElement for Rect {
+  fn elem_size() -> usize { mem::size_of::<CvRect>() }
+  unsafe fn read(elem: *const c_schar) -> Rect {
+    let ref rect = *(elem as *const CvRect);
+    Rect::new(rect.x as i32, rect.y as i32, rect.width as i32, rect.height as i32)
+  }
+}
+
+impl SeqElement for [Point;2] {
+  fn elem_size() -> usize { 2 * mem::size_of::<CvPoint>() }
+  unsafe fn read(elem: *const c_schar) -> [Point;2] {
+    let points = elem as *const CvPoint;
+    [Point::read(points as *const c_schar), Point::read(points.offset(1) as *const c_schar)]
+  }
+}
+
+impl SeqElement for [f32;2] {
+  fn elem_size() -> usize { mem::size_of::<[f32;2]>() }
+  unsafe fn read(elem: *const c_schar) -> [f32;2] {
+    *(elem as *const [f32;2])
+  }
+}
+
+impl SeqElement for [f32;3] {
+  fn elem_size() -> usize { mem::size_of::<[f32;3]>() }
+  unsafe fn read(elem: *const c_schar) -> [f32;3] {
+    *(elem as *const [f32;3])
+  }
+}
+
+impl SeqElement for ConvexityDefect {
+  fn elem_size() -> usize { mem::size_of::<CvConvexityDefect>() }
+  unsafe fn read(elem: *const c_schar) -> ConvexityDefect {
+    let ref defect = *(elem as *const CvConvexityDefect);
+    ConvexityDefect {
+      start: Point::read(defect.start as *const c_schar),
+      end: Point::read(defect.end as *const c_schar),
+      depth_point: Point::read(defect.depth_point as *const c_schar),
+      depth: defect.depth,
     }
   }
 }
 
-impl Seq {
-  fn len(&self) -> usize {
+pub struct MemStorage {
+  raw: *mut CvMemStorage,
+}
+
+impl MemStorage {
+  pub fn new() -> Result<MemStorage> {
+    MemStorage::with_block_size(0)
+  }
+
+  pub fn with_block_size(block_size: u32) -> Result<MemStorage> {
+    if block_size > i32::MAX as u32 {
+      return Err(Error::BadArgument(format!("memory storage blocks hold at most {} bytes, got {}", i32::MAX, block_size)));
+    }
+    match guard(|| unsafe { cvCreateMemStorage(block_size as i32) })? {
+      raw if !raw.is_null() => Ok(MemStorage { raw: raw }),
+      _ => Err(Error::BadArgument(format!("could not allocate memory storage with {}-byte blocks", block_size))),
+    }
+  }
+
+  pub fn clear(&mut self) -> Result<()> {
+    guard(|| unsafe { cvClearMemStorage(self.raw) })
+  }
+
+  #[cfg(any(feature = "imgproc", feature = "objdetect"))]
+  pub(crate) fn as_raw(&self) -> *mut CvMemStorage {
+    self.raw
+  }
+}
+
+// Allocation mutates the block list, so storage can move between threads
+// but must not be shared.
+unsafe impl Send for MemStorage {}
+
+impl Drop for MemStorage {
+  fn drop(&mut self) {
+    unsafe { cvReleaseMemStorage(&mut self.raw); }
+  }
+}
+
+pub struct Seq<'s, T: SeqElement> {
+  raw: *mut CvSeq,
+  _marker: PhantomData<(&'s MemStorage, T)>,
+}
+
+impl<'s, T: SeqElement> Seq<'s, T> {
+  /// Wraps a sequence returned by the C API, or gives `None` for null.
+  ///
+  /// # Safety
+  ///
+  /// A non-null `raw` must point to a sequence allocated in storage that
+  /// outlives `'s`, whose elements have the layout `T::read` expects.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the sequence elements are smaller than `T::elem_size()`.
+  pub(crate) unsafe fn from_raw(raw: *mut CvSeq) -> Option<Seq<'s, T>> {
+    if raw.is_null() {
+      return None;
+    }
+    assert!((*raw).elem_size as usize >= T::elem_size(),
+      "sequence elements of {} bytes cannot hold {} bytes", (*raw).elem_size, T::elem_size());
+    Some(Seq { raw: raw, _marker: PhantomData })
+  }
+
+  pub fn len(&self) -> usize {
     unsafe {
       let total = (*self.raw).total;
       if total.gt(&0) { total as usize } else { 0 }
     }
   }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  pub fn get(&self, index: usize) -> Option<T> {
+    if index >= self.len() {
+      return None;
+    }
+    unsafe {
+      match cvGetSeqElem(&*self.raw, index as i32) {
+        c if !c.is_null() => Some(T::read(c)),
+        _ => None
+      }
+    }
+  }
+
+  pub fn iter(&self) -> SeqIter<'s, T> {
+    SeqIter { seq: self.clone(), curr: 0 }
+  }
+
+  pub fn h_next(&self) -> Option<Seq<'s, T>> {
+    unsafe { Seq::from_raw((*self.raw).h_next) }
+  }
+
+  pub fn v_next(&self) -> Option<Seq<'s, T>> {
+    unsafe { Seq::from_raw((*self.raw).v_next) }
+  }
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[cfg(feature = "imgproc")]
+impl<'s> Seq<'s, Point> {
+  pub fn bounding_rect(&self) -> Result<Rect> {
+    let rect = guard(|| unsafe { cvBoundingRect(self.raw as *const CvArr, 0) })?;
+    Ok(Rect::new(rect.x as i32, rect.y as i32, rect.width as i32, rect.height as i32))
+  }
+
+  pub fn min_area_rect(&self) -> Result<RotatedRect> {
+    let rect = guard(|| unsafe { cvMinAreaRect2(self.raw as *const CvArr, ptr::null_mut()) })?;
+    Ok(RotatedRect::from_raw(&rect))
+  }
+
+  pub fn fit_ellipse(&self) -> Result<RotatedRect> {
+    if self.len() < 5 {
+      return Err(Error::BadArgument(format!("fitting an ellipse needs at least 5 points, got {}", self.len())));
+    }
+    let ellipse = guard(|| unsafe { cvFitEllipse2(self.raw as *const CvArr) })?;
+    Ok(RotatedRect::from_raw(&ellipse))
+  }
+
+  pub fn min_enclosing_circle(&self) -> Result<(Point2f, f32)> {
+    let mut center = CvPoint2D32f { x: 0.0, y: 0.0 };
+    let mut radius = 0.0;
+    guard(|| unsafe { cvMinEnclosingCircle(self.raw as *const CvArr, &mut center, &mut radius) })?;
+    Ok((Point2f::from_raw(&center), radius))
+  }
+
+  // Each defect points into this contour, so the result cannot outlive it either.
+  pub fn convexity_defects<'t>(&self, storage: &'t MemStorage) -> Result<Seq<'t, ConvexityDefect>> where 's: 't {
+    let defects = guard(|| unsafe {
+      let hull = cvConvexHull2(self.raw as *const CvArr, storage.as_raw() as *const _, 1, 0); // CV_CLOCKWISE
+      cvConvexityDefects(self.raw as *const CvArr, hull as *const CvArr, storage.as_raw())
+    })?;
+    Ok(unsafe { Seq::from_raw(defects as *mut CvSeq) }.expect("cvConvexityDefects returned no sequence"))
+  }
+}
+
+impl<'s, T: SeqElement> Clone for Seq<'s, T> {
+  fn clone(&self) -> Seq<'s, T> {
+    Seq { raw: self.raw, _marker: PhantomData }
+  }
+}
+
+impl<'s, T: SeqElement> IntoIterator for Seq<'s, T> {
+  type Item = T;
+  type IntoIter = SeqIter<'s, T>;
+  fn into_iter(self) -> SeqIter<'s, T> {
+    SeqIter { seq: self, curr: 0 }
+  }
+}
+
+impl<'a, 's, T: SeqElement> IntoIterator for &'a Seq<'s, T> {
+  type Item = T;
+  type IntoIter = SeqIter<'s, T>;
+  fn into_iter(self) -> SeqIter<'s, T> {
+    self.iter()
+  }
+}
+
+pub struct SeqIter<'s, T: SeqElement> {
+  seq: Seq<'s, T>,
+  curr: usize,
+}
+
+impl<'s, T: SeqElement> Iterator for SeqIter<'s, T> {
+  type Item = T;
+  fn next(&mut self) -> Option<T> {
+    let elem = self.seq.get(self.curr);
+    if elem.is_some() { self.curr += 1; }
+    elem
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct Size {
   pub width: i32,
   pub height: i32,
@@ -103,4 +559,327 @@ impl Size {
   pub fn new(width: i32, height: i32) -> Size {
     Size { width: width, height: height }
   }
+
+  pub fn area(&self) -> i64 {
+    self.width as i64 * self.height as i64
+  }
+}
+
+impl Add for Size {
+  type Output = Size;
+  fn add(self, other: Size) -> Size {
+    Size::new(self.width + other.width, self.height + other.height)
+  }
+}
+
+impl Sub for Size {
+  type Output = Size;
+  fn sub(self, other: Size) -> Size {
+    Size::new(self.width - other.width, self.height - other.height)
+  }
+}
+
+impl Mul<i32> for Size {
+  type Output = Size;
+  fn mul(self, factor: i32) -> Size {
+    Size::new(self.width * factor, self.height * factor)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Size2f {
+  pub width: f32,
+  pub height: f32,
+}
+
+impl Size2f {
+  pub fn new(width: f32, height: f32) -> Size2f {
+    Size2f { width: width, height: height }
+  }
+
+  pub fn round(&self) -> Size {
+    Size::new(self.width.round() as i32, self.height.round() as i32)
+  }
+}
+
+impl TryFrom<Size> for Size2f {
+  type Error = Error;
+  fn try_from(size: Size) -> Result<Size2f> {
+    Ok(Size2f::new(exact_f32(size.width)?, exact_f32(size.height)?))
+  }
+}
+
+impl TryFrom<Size2f> for Size {
+  type Error = Error;
+  fn try_from(size: Size2f) -> Result<Size> {
+    Ok(Size::new(exact_i32(size.width as f64)?, exact_i32(size.height as f64)?))
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Size2d {
+  pub width: f64,
+  pub height: f64,
+}
+
+impl Size2d {
+  pub fn new(width: f64, height: f64) -> Size2d {
+    Size2d { width: width, height: height }
+  }
+
+  pub fn round(&self) -> Size {
+    Size::new(self.width.round() as i32, self.height.round() as i32)
+  }
+}
+
+impl From<Size> for Size2d {
+  fn from(size: Size) -> Size2d {
+    Size2d::new(size.width as f64, size.height as f64)
+  }
+}
+
+impl From<Size2f> for Size2d {
+  fn from(size: Size2f) -> Size2d {
+    Size2d::new(size.width as f64, size.height as f64)
+  }
+}
+
+impl TryFrom<Size2d> for Size {
+  type Error = Error;
+  fn try_from(size: Size2d) -> Result<Size> {
+    Ok(Size::new(exact_i32(size.width)?, exact_i32(size.height)?))
+  }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct RotatedRect {
+  pub center: Point2f,
+  pub size: Size2f,
+  pub angle: f32,
+}
+
+impl RotatedRect {
+  pub fn new(center: Point2f, size: Size2f, angle: f32) -> RotatedRect {
+    RotatedRect { center: center, size: size, angle: angle }
+  }
+
+  #[cfg(feature = "imgproc")]
+  pub fn points(&self) -> Result<[Point2f;4]> {
+    let mut points = [CvPoint2D32f { x: 0.0, y: 0.0 };4];
+    guard(|| unsafe { cvBoxPoints(self.to_raw(), points.as_mut_ptr()) })?;
+    Ok([Point2f::from_raw(&points[0]), Point2f::from_raw(&points[1]), Point2f::from_raw(&points[2]), Point2f::from_raw(&points[3])])
+  }
+
+  #[cfg(feature = "imgproc")]
+  pub fn bounding_rect(&self) -> Result<Rect> {
+    let points = self.points()?;
+    let left = points.iter().map(|p| p.x).fold(f32::INFINITY, f32::min).floor() as i32;
+    let top = points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min).floor() as i32;
+    let right = points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max).ceil() as i32;
+    let bottom = points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max).ceil() as i32;
+    Ok(Rect::new(left, top, right - left, bottom - top))
+  }
+
+  #[cfg(feature = "imgproc")]
+  pub(crate) fn from_raw(rect: &CvBox2D) -> RotatedRect {
+    RotatedRect::new(Point2f::from_raw(&rect.center), Size2f::new(rect.size.width, rect.size.height), rect.angle)
+  }
+
+  #[cfg(feature = "imgproc")]
+  pub(crate) fn to_raw(&self) -> CvBox2D {
+    CvBox2D {
+      center: self.center.to_raw(),
+      size: CvSize2D32f { width: self.size.width, height: self.size.height },
+      angle: self.angle,
+    }
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NormType {
+  Inf = 1,
+  L1 = 2,
+  L2 = 4,
+}
+
+// `MinMax(low, high)` stretches values onto a range; `Norm(kind, value)`
+// scales them so the chosen norm equals `value`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Normalization {
+  MinMax(f64, f64),
+  Norm(NormType, f64),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum TermCriteria {
+  Count(u32),
+  Eps(f64),
+  CountEps(u32, f64),
+}
+
+impl TermCriteria {
+  pub(crate) fn to_raw(&self) -> CvTermCriteria {
+    match *self {
+      TermCriteria::Count(count) => CvTermCriteria { type_: 1, max_iter: count as i32, epsilon: 0.0 }, // CV_TERMCRIT_ITER
+      TermCriteria::Eps(eps) => CvTermCriteria { type_: 2, max_iter: 0, epsilon: eps }, // CV_TERMCRIT_EPS
+      TermCriteria::CountEps(count, eps) => CvTermCriteria { type_: 1 | 2, max_iter: count as i32, epsilon: eps },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::convert::TryFrom;
+  use super::{Point, Point2d, Point2f, Rect, Size, Size2d, Size2f};
+
+  #[test]
+  fn intersection_of_touching_rects_is_empty() {
+    let a = Rect::new(0, 0, 10, 10);
+    assert_eq!(a.intersection(&Rect::new(10, 0, 5, 5)), None);
+    assert_eq!(a.intersection(&Rect::new(0, 10, 5, 5)), None);
+  }
+
+  #[test]
+  fn intersection_of_disjoint_rects_is_empty() {
+    assert_eq!(Rect::new(0, 0, 10, 10).intersection(&Rect::new(20, 20, 5, 5)), None);
+  }
+
+  #[test]
+  fn intersection_with_contained_rect_is_that_rect() {
+    let inner = Rect::new(2, 3, 4, 5);
+    assert_eq!(Rect::new(0, 0, 10, 10).intersection(&inner), Some(inner));
+    assert_eq!(inner.intersection(&Rect::new(0, 0, 10, 10)), Some(inner));
+  }
+
+  #[test]
+  fn intersection_of_overlapping_rects() {
+    assert_eq!(Rect::new(0, 0, 10, 10).intersection(&Rect::new(5, -5, 10, 10)), Some(Rect::new(5, 0, 5, 5)));
+  }
+
+  #[test]
+  fn iou() {
+    let a = Rect::new(0, 0, 10, 10);
+    assert_eq!(a.iou(&a), 1.0);
+    assert_eq!(a.iou(&Rect::new(2, 3, 4, 5)), 0.2);
+    assert!((a.iou(&Rect::new(5, 0, 10, 10)) - 1.0 / 3.0).abs() < 1e-12);
+    assert_eq!(a.iou(&Rect::new(10, 0, 10, 10)), 0.0);
+    assert_eq!(Rect::new(0, 0, 0, 0).iou(&Rect::new(0, 0, 0, 0)), 0.0);
+  }
+
+  #[test]
+  fn clamp_inside_partly_and_fully_outside() {
+    let size = Size::new(10, 10);
+    assert_eq!(Rect::new(2, 2, 3, 3).clamp(&size), Rect::new(2, 2, 3, 3));
+    assert_eq!(Rect::new(-5, -5, 10, 10).clamp(&size), Rect::new(0, 0, 5, 5));
+    assert_eq!(Rect::new(8, 8, 5, 5).clamp(&size), Rect::new(8, 8, 2, 2));
+    assert_eq!(Rect::new(20, 30, 5, 5).clamp(&size), Rect::new(10, 10, 0, 0));
+    assert_eq!(Rect::new(-20, 5, 5, 5).clamp(&size), Rect::new(0, 5, 0, 0));
+    assert!(Rect::new(20, 30, 5, 5).clamp(&size).is_empty());
+  }
+
+  #[test]
+  fn scale_rounds_the_corners() {
+    assert_eq!(Rect::new(0, 0, 10, 10).scale(2.0), Rect::new(0, 0, 20, 20));
+    assert_eq!(Rect::new(1, 1, 3, 3).scale(1.5), Rect::new(2, 2, 4, 4));
+    assert_eq!(Rect::new(0, 0, 5, 5).scale(0.5), Rect::new(0, 0, 3, 3));
+    assert_eq!(Rect::new(-3, -3, 3, 3).scale(0.5), Rect::new(-2, -2, 2, 2));
+  }
+
+  #[test]
+  fn contains_excludes_the_bottom_right_edge() {
+    let rect = Rect::new(0, 0, 10, 10);
+    assert!(rect.contains(&Point::new(0, 0)));
+    assert!(rect.contains(&Point::new(9, 9)));
+    assert!(!rect.contains(&Point::new(10, 9)));
+    assert!(!rect.contains(&Point::new(9, 10)));
+    assert!(!rect.contains(&rect.br()));
+    assert!(!rect.contains(&Point::new(-1, 0)));
+  }
+
+  #[test]
+  fn rect_accessors() {
+    let rect = Rect::from_points(&Point::new(10, 2), &Point::new(4, 8));
+    assert_eq!(rect, Rect::new(4, 2, 6, 6));
+    assert_eq!(rect.tl(), Point::new(4, 2));
+    assert_eq!(rect.br(), Point::new(10, 8));
+    assert_eq!(rect.center(), Point::new(7, 5));
+    assert_eq!(rect.size(), Size::new(6, 6));
+    assert_eq!(rect.area(), 36);
+    assert_eq!(Rect::new(0, 0, -1, 5).area(), 0);
+    assert_eq!(rect.union_area(&Rect::new(4, 2, 3, 3)), 36);
+  }
+
+  #[test]
+  fn rects_near_the_end_of_the_range() {
+    let rect = Rect::new(i32::MAX - 5, i32::MAX - 5, 10, 10);
+    assert_eq!(rect.br(), Point::new(i32::MAX, i32::MAX));
+    assert_eq!(rect.center(), Point::new(i32::MAX, i32::MAX));
+    assert!(rect.contains(&Point::new(i32::MAX, i32::MAX)));
+    assert!(!rect.contains(&Point::new(i32::MAX - 6, i32::MAX)));
+    assert_eq!(rect.intersection(&Rect::new(i32::MAX - 2, 0, 100, i32::MAX)), Some(Rect::new(i32::MAX - 2, i32::MAX - 5, 7, 5)));
+    assert_eq!(rect.intersection(&rect), Some(rect));
+    let wide = Rect::new(i32::MIN, i32::MIN, i32::MAX, i32::MAX);
+    assert_eq!(wide.intersection(&Rect::new(-1, -1, i32::MAX, i32::MAX)), None);
+    assert_eq!(wide.intersection(&Rect::new(-10, -10, i32::MAX, i32::MAX)), Some(Rect::new(-10, -10, 9, 9)));
+    assert_eq!(Rect::from_points(&Point::new(i32::MIN, 0), &Point::new(i32::MAX, 1)).width, i32::MAX);
+  }
+
+  #[cfg(feature = "imgproc")]
+  #[test]
+  fn bounding_union_covers_both_rects() {
+    let a = Rect::new(0, 0, 10, 10);
+    assert_eq!(a.bounding_union(&Rect::new(5, -5, 10, 10)).unwrap(), Rect::new(0, -5, 15, 15));
+    assert_eq!(a.bounding_union(&Rect::new(2, 3, 4, 5)).unwrap(), a);
+    assert_eq!(a.bounding_union(&Rect::new(20, 20, 5, 5)).unwrap(), Rect::new(0, 0, 25, 25));
+  }
+
+  #[test]
+  fn rect_offsets() {
+    assert_eq!(Rect::new(1, 2, 3, 4) + Point::new(10, 20), Rect::new(11, 22, 3, 4));
+    assert_eq!(Rect::new(1, 2, 3, 4) - Point::new(1, 2), Rect::new(0, 0, 3, 4));
+  }
+
+  #[test]
+  fn point_ops() {
+    assert_eq!(Point::new(1, 2) + Point::new(3, 4), Point::new(4, 6));
+    assert_eq!(Point::new(1, 2) - Point::new(3, 4), Point::new(-2, -2));
+    assert_eq!(Point::new(1, -2) * 3, Point::new(3, -6));
+  }
+
+  #[test]
+  fn size_ops() {
+    assert_eq!(Size::new(1, 2) + Size::new(3, 4), Size::new(4, 6));
+    assert_eq!(Size::new(5, 6) - Size::new(3, 4), Size::new(2, 2));
+    assert_eq!(Size::new(2, 3) * 4, Size::new(8, 12));
+    assert_eq!(Size::new(2, 3).area(), 6);
+  }
+
+  #[test]
+  fn point_conversions_are_exact() {
+    assert_eq!(Point::try_from(Point2f::new(3.0, -4.0)), Ok(Point::new(3, -4)));
+    assert!(Point::try_from(Point2f::new(1.5, 0.0)).is_err());
+    assert_eq!(Point2f::try_from(Point::new(3, -4)), Ok(Point2f::new(3.0, -4.0)));
+    assert!(Point2f::try_from(Point::new(16_777_217, 0)).is_err());
+    assert_eq!(Point2f::new(1.5, -1.5).round(), Point::new(2, -2));
+  }
+
+  #[test]
+  fn widening_conversions_are_lossless() {
+    let far = Point::new(16_777_217, i32::MIN);
+    assert_eq!(Point2d::from(far), Point2d::new(16_777_217.0, i32::MIN as f64));
+    assert_eq!(Point::try_from(Point2d::from(far)), Ok(far));
+    let big = Size::new(16_777_217, i32::MAX);
+    assert_eq!(Size2d::from(big), Size2d::new(16_777_217.0, i32::MAX as f64));
+    assert_eq!(Size::try_from(Size2d::from(big)), Ok(big));
+  }
+
+  #[test]
+  fn size_conversions_are_exact() {
+    assert_eq!(Size2f::try_from(Size::new(3, 4)), Ok(Size2f::new(3.0, 4.0)));
+    assert!(Size2f::try_from(Size::new(16_777_217, 1)).is_err());
+    assert_eq!(Size::try_from(Size2f::new(3.0, 4.0)), Ok(Size::new(3, 4)));
+    assert!(Size::try_from(Size2d::new(0.5, 1.0)).is_err());
+    assert!(Size::try_from(Size2d::new(1e10, 1.0)).is_err());
+    assert_eq!(Size2d::new(1.5, 2.4).round(), Size::new(2, 2));
+  }
 }
@@ -0,0 +1,150 @@
+use std::mem;
+use std::ptr;
+use libc::c_int;
+use ffi::core::*;
+use ffi::types::{CvArr, CvMat, CvRect};
+use arithm::arr;
+use core::Size;
+use error::{Error, Result, guard};
+use image::{Depth, Image, Spectrum};
+use mat::{FloatElement, Mat};
+
+const CV_DXT_INV_SCALE: c_int = 1 | 2;
+const CV_DXT_ROWS: c_int = 4;
+const CV_DXT_MUL_CONJ: c_int = 8;
+
+fn dxt_flags(inverse: bool, rows: bool) -> c_int {
+  (if inverse { CV_DXT_INV_SCALE } else { 0 }) | (if rows { CV_DXT_ROWS } else { 0 })
+}
+
+fn sub_rect(image: &Image, x: i32, y: i32, width: i32, height: i32) -> CvMat {
+  let mut header = unsafe { mem::zeroed::<CvMat>() };
+  unsafe { cvGetSubRect(arr(image), &mut header, CvRect { x: x, y: y, width: width, height: height }); }
+  header
+}
+
+impl Image {
+  pub fn optimal_dft_size(size: &Size) -> Size {
+    unsafe { Size::new(cvGetOptimalDFTSize(size.width as c_int), cvGetOptimalDFTSize(size.height as c_int)) }
+  }
+
+  // Zero-pads on the right and bottom up to the nearest size the DFT handles fast.
+  pub fn pad_to_optimal_dft_size(&self) -> Result<Image> {
+    let padded = Image::new(&Image::optimal_dft_size(&self.size()), self.depth()?, self.channels())?;
+    guard(|| unsafe {
+      let roi = sub_rect(&padded, 0, 0, self.width(), self.height());
+      cvCopy(arr(self), &roi as *const CvMat as *const CvArr, ptr::null());
+    })?;
+    Ok(padded)
+  }
+
+  pub fn dft(&self, spectrum: Spectrum, rows: bool) -> Result<Image> {
+    self.expect_depth(&[Depth::F32, Depth::F64], "cvDFT")?;
+    self.expect_channels(&[1, 2], "cvDFT")?;
+    let channels = if spectrum == Spectrum::Complex { 2 } else { self.channels() };
+    Image::new_with(&self.size(), self.depth()?, channels, |dst| unsafe { cvDFT(arr(self), dst, dxt_flags(false, rows), 0) })
+  }
+
+  // The inverse is scaled, so `idft(dft(x)) == x`. A complex spectrum can be
+  // folded back to a real image with `real_output`.
+  pub fn idft(&self, real_output: bool, rows: bool) -> Result<Image> {
+    self.expect_depth(&[Depth::F32, Depth::F64], "cvDFT")?;
+    self.expect_channels(&[1, 2], "cvDFT")?;
+    let channels = if real_output { 1 } else { self.channels() };
+    Image::new_with(&self.size(), self.depth()?, channels, |dst| unsafe { cvDFT(arr(self), dst, dxt_flags(true, rows), 0) })
+  }
+
+  pub fn dct(&self, rows: bool) -> Result<Image> {
+    self.dct_image(false, rows)
+  }
+
+  pub fn idct(&self, rows: bool) -> Result<Image> {
+    self.dct_image(true, rows)
+  }
+
+  fn dct_image(&self, inverse: bool, rows: bool) -> Result<Image> {
+    self.expect_depth(&[Depth::F32, Depth::F64], "cvDCT")?;
+    self.expect_channels(&[1], "cvDCT")?;
+    self.unary(|src, dst| unsafe { cvDCT(src, dst, dxt_flags(inverse, rows)) })
+  }
+
+  // Multiplies two spectra of the same layout; `conjugate` uses the
+  // conjugate of `other`, as cross-correlation and phase correlation need.
+  pub fn mul_spectrums(&self, other: &Image, conjugate: bool, rows: bool) -> Result<Image> {
+    self.expect_depth(&[Depth::F32, Depth::F64], "cvMulSpectrums")?;
+    self.expect_channels(&[1, 2], "cvMulSpectrums")?;
+    let flags = (if conjugate { CV_DXT_MUL_CONJ } else { 0 }) | (if rows { CV_DXT_ROWS } else { 0 });
+    self.binary(other, None, "cvMulSpectrums", |src1, src2, dst| unsafe { cvMulSpectrums(src1, src2, dst, flags) })
+  }
+
+  pub fn magnitude(&self) -> Result<Image> {
+    self.expect_depth(&[Depth::F32, Depth::F64], "cvCartToPolar")?;
+    self.expect_channels(&[2], "cvCartToPolar")?;
+    let parts = self.split()?;
+    Image::new_with(&self.size(), self.depth()?, 1, |dst| unsafe { cvCartToPolar(arr(&parts[0]), arr(&parts[1]), dst, ptr::null(), 0) })
+  }
+
+  // `log(1 + |F|)` of a complex spectrum with the zero frequency moved to the centre.
+  pub fn log_spectrum(&self) -> Result<Image> {
+    let magnitude = self.magnitude()?.add_scalar(&[1.0;4], None)?;
+    magnitude.unary(|src, dst| unsafe { cvLog(src, dst) })?.fft_shift()
+  }
+
+  // Swaps quadrants so the zero frequency sits at `(width / 2, height / 2)`.
+  pub fn fft_shift(&self) -> Result<Image> {
+    let dst = Image::new(&self.size(), self.depth()?, self.channels())?;
+    let (w, h) = (self.width(), self.height());
+    let (cx, cy) = (w / 2, h / 2);
+    let blocks = [
+      (0, 0, cx, cy, w - cx, h - cy),
+      (w - cx, 0, 0, cy, cx, h - cy),
+      (0, h - cy, cx, 0, w - cx, cy),
+      (w - cx, h - cy, 0, 0, cx, cy),
+    ];
+    guard(|| unsafe {
+      for &(sx, sy, dx, dy, bw, bh) in blocks.iter().filter(|b| b.4 > 0 && b.5 > 0) {
+        let from = sub_rect(self, sx, sy, bw, bh);
+        let to = sub_rect(&dst, dx, dy, bw, bh);
+        cvCopy(&from as *const CvMat as *const CvArr, &to as *const CvMat as *const CvArr, ptr::null());
+      }
+    })?;
+    Ok(dst)
+  }
+
+}
+
+// Matrices hold real data, so their spectra use the packed CCS layout.
+impl<T: FloatElement> Mat<T> {
+  pub fn dft(&self, rows: bool) -> Result<Mat<T>> {
+    self.dxt(|src, dst| unsafe { cvDFT(src, dst, dxt_flags(false, rows), 0) })
+  }
+
+  pub fn idft(&self, rows: bool) -> Result<Mat<T>> {
+    self.dxt(|src, dst| unsafe { cvDFT(src, dst, dxt_flags(true, rows), 0) })
+  }
+
+  pub fn dct(&self, rows: bool) -> Result<Mat<T>> {
+    self.dxt(|src, dst| unsafe { cvDCT(src, dst, dxt_flags(false, rows)) })
+  }
+
+  pub fn idct(&self, rows: bool) -> Result<Mat<T>> {
+    self.dxt(|src, dst| unsafe { cvDCT(src, dst, dxt_flags(true, rows)) })
+  }
+
+  pub fn mul_spectrums(&self, other: &Mat<T>, conjugate: bool, rows: bool) -> Result<Mat<T>> {
+    if (self.rows(), self.cols()) != (other.rows(), other.cols()) {
+      return Err(Error::BadArgument(format!("cvMulSpectrums needs spectra of the same size, got {}x{} and {}x{}",
+        self.rows(), self.cols(), other.rows(), other.cols())));
+    }
+    let flags = (if conjugate { CV_DXT_MUL_CONJ } else { 0 }) | (if rows { CV_DXT_ROWS } else { 0 });
+    let dst = Mat::new(self.rows(), self.cols())?;
+    guard(|| unsafe { cvMulSpectrums(self.as_arr(), other.as_arr(), dst.as_arr(), flags) })?;
+    Ok(dst)
+  }
+
+  fn dxt<F: FnOnce(*const CvArr, *const CvArr)>(&self, f: F) -> Result<Mat<T>> {
+    let dst = Mat::new(self.rows(), self.cols())?;
+    guard(|| f(self.as_arr(), dst.as_arr()))?;
+    Ok(dst)
+  }
+}
@@ -0,0 +1,114 @@
+use std::cell::RefCell;
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::ptr;
+use std::result;
+use std::sync::Once;
+use libc::{c_char, c_int, c_void};
+use ffi::core::*;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Error {
+  Io(String),
+  UnsupportedFormat(String),
+  BadArgument(String),
+  Native { status: i32, func: String, message: String, file: String, line: i32 },
+}
+
+pub type Result<T> = result::Result<T, Error>;
+
+impl Error {
+  pub(crate) fn from_path(path: &Path) -> Error {
+    let name = path.display().to_string();
+    match fs::metadata(path) {
+      Ok(ref metadata) if metadata.is_file() => Error::UnsupportedFormat(name),
+      _ => Error::Io(name),
+    }
+  }
+
+  // Writers fail alike for a missing directory and an unknown extension or
+  // codec; only the directory can be checked afterwards.
+  pub(crate) fn from_output_path(path: &Path, format: String) -> Error {
+    match path.parent() {
+      Some(dir) if dir.as_os_str().is_empty() || dir.is_dir() => Error::UnsupportedFormat(format),
+      _ => Error::Io(path.display().to_string()),
+    }
+  }
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Error::Io(ref what) => write!(f, "could not open {}", what),
+      Error::UnsupportedFormat(ref what) => write!(f, "unsupported format: {}", what),
+      Error::BadArgument(ref what) => write!(f, "bad argument: {}", what),
+      Error::Native { status, ref func, ref message, ref file, line } =>
+        write!(f, "OpenCV error {} in {} ({}:{}): {}", status, func, file, line, message),
+    }
+  }
+}
+
+impl error::Error for Error {}
+
+thread_local!(static LAST_ERROR: RefCell<Option<Error>> = RefCell::new(None));
+static REDIRECT: Once = Once::new();
+
+fn to_string(s: *const c_char) -> String {
+  if s.is_null() {
+    String::new()
+  } else {
+    unsafe { CStr::from_ptr(s).to_string_lossy().into_owned() }
+  }
+}
+
+extern "C" fn on_error(status: c_int, func_name: *const c_char, err_msg: *const c_char,
+  file_name: *const c_char, line: c_int, _: *mut c_void) -> c_int {
+
+  let error = Error::Native {
+    status: status as i32,
+    func: to_string(func_name),
+    message: to_string(err_msg),
+    file: to_string(file_name),
+    line: line as i32,
+  };
+  LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+  0
+}
+
+// `cvrs_guard` runs this between its try and catch, so a native exception
+// unwinds through these frames; both sides use the C-unwind ABI for that.
+extern "C-unwind" fn call<T, F: FnOnce() -> T>(data: *mut c_void) {
+  let state = unsafe { &mut *(data as *mut (Option<F>, Option<T>)) };
+  if let Some(f) = state.0.take() {
+    state.1 = Some(f());
+  }
+}
+
+pub(crate) fn guard<T, F: FnOnce() -> T>(f: F) -> Result<T> {
+  REDIRECT.call_once(|| unsafe {
+    cvRedirectError(on_error, ptr::null_mut(), ptr::null_mut());
+  });
+  LAST_ERROR.with(|last| *last.borrow_mut() = None);
+
+  let mut state = (Some(f), None);
+  let mut message = [0 as c_char; 512];
+  let thrown = unsafe {
+    cvrs_guard(call::<T, F>, &mut state as *mut (Option<F>, Option<T>) as *mut c_void, message.as_mut_ptr(), message.len())
+  };
+
+  match (LAST_ERROR.with(|last| last.borrow_mut().take()), state.1) {
+    (Some(error), _) => Err(error),
+    (None, Some(value)) if thrown == 0 => Ok(value),
+    // Exceptions that bypassed cv::error, such as std::bad_alloc.
+    _ => Err(Error::Native {
+      status: -2, // CV_StsError
+      func: String::new(),
+      message: to_string(message.as_ptr()),
+      file: String::new(),
+      line: 0,
+    }),
+  }
+}
@@ -1,19 +1,104 @@
-use ffi::types::{CvArr, CvMat, CvMemStorage, CvSeq, CvSize, IplImage};
-use libc::{c_char, c_int, c_schar, c_void};
+use ffi::types::{CvArr, CvAttrList, CvFileNode, CvFileStorage, CvMat, CvMemStorage, CvPoint, CvRect, CvScalar, CvSeq, CvSize, CvTermCriteria, IplImage};
+use libc::{c_char, c_double, c_int, c_schar, c_void, size_t};
 
-#[link(name = "opencv_core")]
-extern "C" {
+pub type CvErrorCallback = extern "C" fn(status: c_int, func_name: *const c_char, err_msg: *const c_char, file_name: *const c_char, line: c_int, userdata: *mut c_void) -> c_int;
+
+extern "C-unwind" {
+  pub fn cvAbsDiff(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr);
+  pub fn cvAbsDiffS(src: *const CvArr, dst: *const CvArr, value: CvScalar);
+  pub fn cvAdd(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvAddS(src: *const CvArr, value: CvScalar, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvAddWeighted(src1: *const CvArr, alpha: c_double, src2: *const CvArr, beta: c_double, gamma: c_double, dst: *const CvArr);
+  pub fn cvAnd(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvAndS(src: *const CvArr, value: CvScalar, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvAvg(arr: *const CvArr, mask: *const CvArr) -> CvScalar;
+  pub fn cvAvgSdv(arr: *const CvArr, mean: *mut CvScalar, std_dev: *mut CvScalar, mask: *const CvArr);
+  pub fn cvCartToPolar(x: *const CvArr, y: *const CvArr, magnitude: *const CvArr, angle: *const CvArr, angle_in_degrees: c_int);
+  pub fn cvClearMemStorage(storage: *mut CvMemStorage);
   pub fn cvCloneImage(image: *const IplImage) -> *const IplImage;
   pub fn cvCloneMat(mat: *const CvMat) -> *const CvMat;
+  pub fn cvConvertScale(src: *const CvArr, dst: *const CvArr, scale: c_double, shift: c_double);
+  pub fn cvConvertScaleAbs(src: *const CvArr, dst: *const CvArr, scale: c_double, shift: c_double);
+  pub fn cvCopy(src: *const CvArr, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvCountNonZero(arr: *const CvArr) -> c_int;
+  pub fn cvCreateImage(size: CvSize, depth: c_int, channels: c_int) -> *const IplImage;
+  pub fn cvCreateMat(rows: c_int, cols: c_int, type_: c_int) -> *const CvMat;
   pub fn cvCreateMemStorage(block_size: c_int) -> *mut CvMemStorage;
+  pub fn cvCrossProduct(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr);
+  pub fn cvDCT(src: *const CvArr, dst: *const CvArr, flags: c_int);
+  pub fn cvDFT(src: *const CvArr, dst: *const CvArr, flags: c_int, nonzero_rows: c_int);
+  pub fn cvDet(mat: *const CvArr) -> c_double;
+  pub fn cvDiv(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr, scale: c_double);
+  pub fn cvEigenVV(mat: *const CvArr, evects: *const CvArr, evals: *const CvArr, eps: c_double, lowindex: c_int, highindex: c_int);
+  pub fn cvEndWriteStruct(fs: *mut CvFileStorage);
+  pub fn cvFlip(src: *const CvArr, dst: *const CvArr, flip_mode: c_int);
+  pub fn cvGEMM(src1: *const CvArr, src2: *const CvArr, alpha: c_double, src3: *const CvArr, beta: c_double, dst: *const CvArr, t_abc: c_int);
+  pub fn cvGetFileNodeByName(fs: *const CvFileStorage, map: *const CvFileNode, name: *const c_char) -> *mut CvFileNode;
+  pub fn cvGetImageCOI(image: *const IplImage) -> c_int;
+  pub fn cvGetOptimalDFTSize(size0: c_int) -> c_int;
+  pub fn cvGetRootFileNode(fs: *const CvFileStorage, stream_index: c_int) -> *mut CvFileNode;
   pub fn cvGetSeqElem(seq: *const CvSeq, index: i32) -> *mut c_schar;
   pub fn cvGetSize(mat: *const CvArr) -> CvSize;
+  pub fn cvGetSubRect(arr: *const CvArr, submat: *mut CvMat, rect: CvRect) -> *mut CvMat;
+  pub fn cvInitMatHeader(mat: *mut CvMat, rows: c_int, cols: c_int, type_: c_int, data: *mut c_void, step: c_int) -> *mut CvMat;
+  pub fn cvInRangeS(src: *const CvArr, lower: CvScalar, upper: CvScalar, dst: *const CvArr);
+  pub fn cvInvert(src: *const CvArr, dst: *const CvArr, method: c_int) -> c_double;
+  pub fn cvKMeans2(
+    samples: *const CvArr,
+    cluster_count: c_int,
+    labels: *const CvArr,
+    termcrit: CvTermCriteria,
+    attempts: c_int,
+    rng: *mut u64,
+    flags: c_int,
+    centers: *const CvArr,
+    compactness: *mut c_double
+  ) -> c_int;
   pub fn cvLoad(
     filename: *const c_char,
     memstorage: *mut CvMemStorage,
     name: *const c_char,
     real_name: *const c_char
   ) -> *mut c_void;
+  pub fn cvLog(src: *const CvArr, dst: *const CvArr);
+  pub fn cvLUT(src: *const CvArr, dst: *const CvArr, lut: *const CvArr);
+  pub fn cvMerge(src0: *const CvArr, src1: *const CvArr, src2: *const CvArr, src3: *const CvArr, dst: *const CvArr);
+  pub fn cvMixChannels(src: *const *const CvArr, src_count: c_int, dst: *const *const CvArr, dst_count: c_int, from_to: *const c_int, pair_count: c_int);
+  pub fn cvMinMaxLoc(arr: *const CvArr, min_val: *mut c_double, max_val: *mut c_double, min_loc: *mut CvPoint, max_loc: *mut CvPoint, mask: *const CvArr);
+  pub fn cvMul(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr, scale: c_double);
+  pub fn cvMulSpectrums(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr, flags: c_int);
+  pub fn cvNorm(arr1: *const CvArr, arr2: *const CvArr, norm_type: c_int, mask: *const CvArr) -> c_double;
+  pub fn cvNormalize(src: *const CvArr, dst: *const CvArr, a: c_double, b: c_double, norm_type: c_int, mask: *const CvArr);
+  pub fn cvNot(src: *const CvArr, dst: *const CvArr);
+  pub fn cvOpenFileStorage(filename: *const c_char, memstorage: *mut CvMemStorage, flags: c_int, encoding: *const c_char) -> *mut CvFileStorage;
+  pub fn cvOr(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvOrS(src: *const CvArr, value: CvScalar, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvPerspectiveTransform(src: *const CvArr, dst: *const CvArr, mat: *const CvMat);
+  pub fn cvRead(fs: *mut CvFileStorage, node: *mut CvFileNode, attributes: *mut CvAttrList) -> *mut c_void;
+  pub fn cvRedirectError(error_handler: CvErrorCallback, userdata: *mut c_void, prev_userdata: *mut *mut c_void) -> Option<CvErrorCallback>;
+  pub fn cvRelease(struct_ptr: *mut *mut c_void);
+  pub fn cvReleaseFileStorage(fs: *mut *mut CvFileStorage);
   pub fn cvReleaseImage(image: *const *const IplImage);
   pub fn cvReleaseMat(mat: *const *const CvMat);
+  pub fn cvReleaseMemStorage(storage: *mut *mut CvMemStorage);
+  pub fn cvRepeat(src: *const CvArr, dst: *const CvArr);
+  pub fn cvReshape(arr: *const CvArr, header: *mut CvMat, new_cn: c_int, new_rows: c_int) -> *mut CvMat;
+  pub fn cvSave(filename: *const c_char, struct_ptr: *const c_void, name: *const c_char, comment: *const c_char, attributes: CvAttrList);
+  pub fn cvSetImageCOI(image: *const IplImage, coi: c_int);
+  pub fn cvSetZero(arr: *const CvArr);
+  pub fn cvSolve(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr, method: c_int) -> c_int;
+  pub fn cvSplit(src: *const CvArr, dst0: *const CvArr, dst1: *const CvArr, dst2: *const CvArr, dst3: *const CvArr);
+  pub fn cvStartWriteStruct(fs: *mut CvFileStorage, name: *const c_char, struct_flags: c_int, type_name: *const c_char, attributes: CvAttrList);
+  pub fn cvSub(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvSubRS(src: *const CvArr, value: CvScalar, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvSVD(a: *const CvArr, w: *const CvArr, u: *const CvArr, v: *const CvArr, flags: c_int);
+  pub fn cvSum(arr: *const CvArr) -> CvScalar;
+  pub fn cvTranspose(src: *const CvArr, dst: *const CvArr);
+  pub fn cvWrite(fs: *mut CvFileStorage, name: *const c_char, ptr: *const c_void, attributes: CvAttrList);
+  pub fn cvWriteInt(fs: *mut CvFileStorage, name: *const c_char, value: c_int);
+  pub fn cvWriteReal(fs: *mut CvFileStorage, name: *const c_char, value: c_double);
+  pub fn cvWriteString(fs: *mut CvFileStorage, name: *const c_char, str_: *const c_char, quote: c_int);
+  pub fn cvXor(src1: *const CvArr, src2: *const CvArr, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvXorS(src: *const CvArr, value: CvScalar, dst: *const CvArr, mask: *const CvArr);
+  pub fn cvrs_guard(body: extern "C-unwind" fn(*mut c_void), data: *mut c_void, message: *mut c_char, message_size: size_t) -> c_int;
 }
@@ -0,0 +1,8 @@
+use libc::c_int;
+use ffi::types::{CvArr, CvMemStorage, CvSeq};
+
+extern "C-unwind" {
+  // Fills a sequence of CvKeyPoint.
+  #[link_name = "cvrs_fast"]
+  pub fn cvFAST(image: *const CvArr, threshold: c_int, nonmax_suppression: c_int, storage: *mut CvMemStorage) -> *mut CvSeq;
+}
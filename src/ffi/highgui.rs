@@ -1,8 +1,7 @@
 use libc::{c_char, c_int, c_void};
-use ffi::types::{CvMat, IplImage};
+use ffi::types::IplImage;
 
-#[link(name = "opencv_highgui")]
-extern "C" {
+extern "C-unwind" {
   pub fn cvDestroyWindow(name: *const c_char);
   pub fn cvNamedWindow(name: *const c_char, flags: c_int) -> c_int;
   pub fn cvShowImage(name: *const c_char, image: *const IplImage);
@@ -13,9 +12,4 @@ extern "C" {
   pub fn cvSetTrackbarPos(trackbar_name: *const c_char, window_name: *const c_char, pos: c_int);
   pub fn cvSetMouseCallback(window_name: *const c_char, on_mouse: extern "C" fn (event: c_int, x: c_int, y: c_int, flags: c_int, param: *const c_void), param: *const c_void);
   pub fn cvWaitKey(delay: c_int) -> c_int;
-
-  pub fn cvDecodeImage(buf: *const CvMat, iscolor: c_int) -> *const IplImage;
-  pub fn cvEncodeImage(ext: *const c_char, image: *const CvMat, params: *const c_int) -> *const CvMat;
-  pub fn cvLoadImage(filename: *const c_char, iscolor: c_int) -> *const IplImage;
-  pub fn cvSaveImage(filename: *const c_char, image: *const IplImage, params: *const c_int) -> c_int;
 }
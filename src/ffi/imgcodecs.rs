@@ -0,0 +1,22 @@
+use libc::{c_char, c_int};
+use ffi::types::{CvMat, IplImage};
+
+#[cfg(not(opencv_4))]
+extern "C-unwind" {
+  pub fn cvDecodeImage(buf: *const CvMat, iscolor: c_int) -> *const IplImage;
+  pub fn cvEncodeImage(ext: *const c_char, image: *const CvMat, params: *const c_int) -> *const CvMat;
+  pub fn cvLoadImage(filename: *const c_char, iscolor: c_int) -> *const IplImage;
+  pub fn cvSaveImage(filename: *const c_char, image: *const IplImage, params: *const c_int) -> c_int;
+}
+
+#[cfg(opencv_4)]
+extern "C-unwind" {
+  #[link_name = "cvrs_decode_image"]
+  pub fn cvDecodeImage(buf: *const CvMat, iscolor: c_int) -> *const IplImage;
+  #[link_name = "cvrs_encode_image"]
+  pub fn cvEncodeImage(ext: *const c_char, image: *const CvMat, params: *const c_int) -> *const CvMat;
+  #[link_name = "cvrs_load_image"]
+  pub fn cvLoadImage(filename: *const c_char, iscolor: c_int) -> *const IplImage;
+  #[link_name = "cvrs_save_image"]
+  pub fn cvSaveImage(filename: *const c_char, image: *const IplImage, params: *const c_int) -> c_int;
+}
@@ -1,8 +1,7 @@
 use libc::{c_double, c_float, c_int, c_void};
 use ffi::types::{CvArr, CvBox2D, CvChain, CvChainPtReader, CvConnectedComp, CvContour, CvContourScanner, CvFont, CvHistogram, CvHuMoments, CvLineIterator, CvMat, CvMemStorage, CvMoments, CvPoint, CvPoint2D32f, CvRect, CvScalar, CvSeq, CvSeqBlock, CvSize, CvSlice, CvTermCriteria, IplConvKernel}; 
 
-#[link(name = "opencv_imgproc")]
-extern "C" {
+extern "C-unwind" {
   pub fn cvAcc(image: *const CvArr, sum: *const CvArr, mask: *const CvArr); 
   pub fn cvSquareAcc(image: *const CvArr, sqsum: *const CvArr, mask: *const CvArr);
   pub fn cvMultiplyAcc(image1: *const CvArr, image2: *const CvArr, acc: *const CvArr, mask: *const CvArr);
@@ -30,10 +29,6 @@ extern "C" {
   pub fn cvConvertMaps(mapx: *const CvArr, mapy: *const CvArr, mapxy: *const CvArr, mapalpha: *const CvArr);
   pub fn cvLogPolar(src: *const CvArr, dst: *const CvArr, center: CvPoint2D32f, M: c_double, flags: c_int);
   pub fn cvLinearPolar(src: *const CvArr, dst: *const CvArr, center: CvPoint2D32f, maxRadius: c_double, flags: c_int);
-  pub fn cvUndistort2(src: *const CvArr, dst: *const CvArr, camera_matrix: *const CvMat, distortion_coeffs: *const CvMat, new_camera_matrix: *const CvMat);
-  pub fn cvInitUndistortMap(camera_matrix: *const CvMat, distortion_coeffs: *const CvMat, mapx: *const CvArr, mapy: *const CvArr);
-  pub fn cvInitUndistortRectifyMap(camera_matrix: *const CvMat, dist_coeffs: *const CvMat, R: *const CvMat, new_camera_matrix: *const CvMat, mapx: *const CvArr, mapy: *const CvArr);
-  pub fn cvUndistortPoints(src: *const CvMat, dst: *const CvMat, camera_matrix: *const CvMat, dist_coeffs: *const CvMat, R: *const CvMat, P: *const CvMat);
   pub fn cvCreateStructuringElementEx(cols: c_int, rows: c_int, anchor_x: c_int, anchor_y: c_int, shape: c_int, value: *const c_int) -> *const IplConvKernel;
   pub fn cvReleaseStructuringElement(element: *const *const IplConvKernel);
   pub fn cvErode(src: *const CvArr, dst: *const CvArr, element: *const IplConvKernel, iterations: c_int);
@@ -49,27 +44,27 @@ extern "C" {
   pub fn cvGetQuadrangleSubPix(src: *const CvArr, dst: *const CvArr, map_matrix: *const CvMat);
   pub fn cvMatchTemplate(image: *const CvArr, temp: *const CvArr, result: *const CvArr, method: c_int);
   pub fn cvCalcEMD2(signature1: *const CvArr, signature2: *const CvArr, distance_type: c_int, distance_func: extern "C" fn(a: *const c_float, b: *const c_float, c: *const c_float, user_param: *const c_void) -> c_float, const_matrix: *const CvArr, flow: *const CvArr, lower_bound: *const c_float, userdata: *const c_void) -> c_float;
-  pub fn cvFindContours(image: *const CvArr, storage: CvMemStorage, first_contour: *const *const CvSeq, header_size: c_int, mode: c_int, method: c_int, offset: CvPoint) -> c_int;
-  pub fn cvStartFindContours(image: *const CvArr, storage: CvMemStorage, header_size: c_int, mode: c_int, method: c_int, offset: CvPoint) -> CvContourScanner;
-  pub fn cvFindNextContour(scanner: CvContourScanner) -> *const CvSeq;
-  pub fn cvSubstituteContour(scanner: CvContourScanner, new_contour: *const CvSeq);
-  pub fn cvEndFindContours(scanner: *const CvContourScanner) -> *const CvSeq;
-  pub fn cvApproxChains(src_seq: *const CvSeq, storage: CvMemStorage, method: c_int, parameter: c_double, minimal_perimeter: c_int, recursive: c_int) -> *const CvSeq;
+  pub fn cvFindContours(image: *const CvArr, storage: *mut CvMemStorage, first_contour: *mut *mut CvSeq, header_size: c_int, mode: c_int, method: c_int, offset: CvPoint) -> c_int;
+  pub fn cvStartFindContours(image: *const CvArr, storage: *mut CvMemStorage, header_size: c_int, mode: c_int, method: c_int, offset: CvPoint) -> *mut CvContourScanner;
+  pub fn cvFindNextContour(scanner: *mut CvContourScanner) -> *const CvSeq;
+  pub fn cvSubstituteContour(scanner: *mut CvContourScanner, new_contour: *const CvSeq);
+  pub fn cvEndFindContours(scanner: *mut *mut CvContourScanner) -> *const CvSeq;
+  pub fn cvApproxChains(src_seq: *const CvSeq, storage: *mut CvMemStorage, method: c_int, parameter: c_double, minimal_perimeter: c_int, recursive: c_int) -> *const CvSeq;
   pub fn cvStartReadChainPoints(chain: *const CvChain, reader: *const CvChainPtReader);
   pub fn cvReadChainPoint(reader: *const CvChainPtReader) -> CvPoint;
-  pub fn cvApproxPoly(src_seq: *const c_void, header_size: c_int, storage: CvMemStorage, method: c_int, eps: c_double, recursive: c_int) -> *const CvSeq;
+  pub fn cvApproxPoly(src_seq: *const c_void, header_size: c_int, storage: *mut CvMemStorage, method: c_int, eps: c_double, recursive: c_int) -> *const CvSeq;
   pub fn cvArcLength(curve: *const c_void, slice: CvSlice, is_closed: c_int) -> c_double;
   pub fn cvBoundingRect(points: *const CvArr, update: c_int) -> CvRect; 
   pub fn cvContourArea(contour: *const CvArr, slice: CvSlice, oriented: c_int) -> c_double;
-  pub fn cvMinAreaRect2(points: *const CvArr, storage: CvMemStorage) -> CvBox2D; 
-  pub fn cvMinEnclosingCircle(points: *const CvArr, center: *const CvPoint2D32f, radius: *const c_float) -> c_int;
+  pub fn cvMinAreaRect2(points: *const CvArr, storage: *mut CvMemStorage) -> CvBox2D; 
+  pub fn cvMinEnclosingCircle(points: *const CvArr, center: *mut CvPoint2D32f, radius: *mut c_float) -> c_int;
   pub fn cvMatchShapes(object1: *const c_void, object2: *const c_void, method: c_int, parameter: c_double) -> c_double;
   pub fn cvConvexHull2(input: *const CvArr, hull_storage: *const c_void, orientation: c_int, return_points: c_int) -> *const CvSeq;
   pub fn cvCheckContourConvexity(contour: *const CvArr) -> c_int;
-  pub fn cvConvexityDefects(contour: *const CvArr, convexhull: *const CvArr, storage: CvMemStorage) -> *const CvSeq; 
+  pub fn cvConvexityDefects(contour: *const CvArr, convexhull: *const CvArr, storage: *mut CvMemStorage) -> *const CvSeq; 
   pub fn cvFitEllipse2(points: *const CvArr) -> CvBox2D;
   pub fn cvMaxRect(rect1: *const CvRect, rect2: *const CvRect) -> CvRect;
-  pub fn cvBoxPoints(box_: CvBox2D, pt: *const CvPoint2D32f);
+  pub fn cvBoxPoints(box_: CvBox2D, pt: *mut CvPoint2D32f);
   pub fn cvPointSeqFromMat(seq_kind: c_int, mat: *const CvArr, contour_header: *const CvContour, block: *const CvSeqBlock) -> *const CvSeq;
   pub fn cvPointPolygonTest(contour: *const CvArr, pt: CvPoint2D32f, measure_dist: c_int) -> c_double; 
   pub fn cvCreateHist(dims: c_int, sizes: *const c_int, type_: c_int, ranges: *const *const c_float, uniform: c_int) -> *const CvHistogram;
@@ -97,7 +92,7 @@ extern "C" {
   pub fn cvCornerEigenValsAndVecs(image: *const CvArr, eigenvv: *const CvArr, block_size: c_int, aperture_size: c_int);
   pub fn cvCornerMinEigenVal(image: *const CvArr, eigenval: *const CvArr, block_size: c_int, aperture_size: c_int);
   pub fn cvCornerHarris(image: *const CvArr, harris_response: *const CvArr, block_size: c_int, aperture_size: c_int, k: c_double);
-  pub fn cvFindCornerSubPix(image: *const CvArr, corners: *const CvPoint2D32f, count: c_int, win: CvSize, zero_zone: CvSize, criteria: CvTermCriteria);
+  pub fn cvFindCornerSubPix(image: *const CvArr, corners: *mut CvPoint2D32f, count: c_int, win: CvSize, zero_zone: CvSize, criteria: CvTermCriteria);
   pub fn cvGoodFeaturesToTrack(image: *const CvArr, eig_image: *const CvArr, temp_image: *const CvArr, corners: *const CvPoint2D32f, corner_count: *const c_int, quality_level: c_double, min_distance: c_double, mask: *const CvArr, block_size: c_int, use_harris: c_int, k: c_double);
   pub fn cvHoughLines2(image: *const CvArr, line_storage: *const c_void, method: c_int, rho: c_double, theta: c_double, threshold: c_int, param1: c_double, param2: c_double, min_theta: c_double, max_theta: c_double) -> *const CvSeq;
   pub fn cvHoughCircles(image: *const CvArr, circle_storage: *const c_void, method: c_int, dp: c_double, min_dist: c_double, param1: c_double, param2: c_double, min_radius: c_int, max_radius: c_int) -> *const CvSeq;
@@ -119,3 +114,12 @@ extern "C" {
   pub fn cvEllipse2Poly(center: CvPoint, axes: CvSize, angle: c_int, arc_start: c_int, arc_end: c_int, pts: *const CvPoint, delta: c_int) -> c_int;
   pub fn cvDrawContours(img: *const CvArr, contour: *const CvSeq, external_color: CvScalar, hole_color: CvScalar, max_level: c_int, thickness: c_int, line_type: c_int, offset: CvPoint);
 }
+
+// Undistortion moved from imgproc to calib3d in OpenCV 4.
+#[cfg(any(not(opencv_4), feature = "calib3d"))]
+extern "C-unwind" {
+  pub fn cvUndistort2(src: *const CvArr, dst: *const CvArr, camera_matrix: *const CvMat, distortion_coeffs: *const CvMat, new_camera_matrix: *const CvMat);
+  pub fn cvInitUndistortMap(camera_matrix: *const CvMat, distortion_coeffs: *const CvMat, mapx: *const CvArr, mapy: *const CvArr);
+  pub fn cvInitUndistortRectifyMap(camera_matrix: *const CvMat, dist_coeffs: *const CvMat, R: *const CvMat, new_camera_matrix: *const CvMat, mapx: *const CvArr, mapy: *const CvArr);
+  pub fn cvUndistortPoints(src: *const CvMat, dst: *const CvMat, camera_matrix: *const CvMat, dist_coeffs: *const CvMat, R: *const CvMat, P: *const CvMat);
+}
@@ -1,6 +1,13 @@
 pub mod core;
+#[cfg(feature = "features2d")]
+pub mod features2d;
+#[cfg(feature = "imgproc")]
 pub mod imgproc;
+pub mod imgcodecs;
+#[cfg(feature = "objdetect")]
 pub mod objdetect;
 pub mod types;
+#[cfg(feature = "highgui")]
 pub mod highgui;
+#[cfg(feature = "video")]
 pub mod videoio;
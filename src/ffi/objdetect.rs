@@ -1,9 +1,24 @@
 use ffi::types::{CvArr, CvHaarClassifierCascade, CvMemStorage, CvSeq, CvSize};
 use libc::{c_int, c_double};
+#[cfg(opencv_4)]
+use libc::c_char;
 
-#[link(name = "opencv_objdetect")]
-extern "C" {
+#[cfg(not(opencv_4))]
+extern "C-unwind" {
   pub fn cvHaarDetectObjects(image: *const CvArr, cascade: *mut CvHaarClassifierCascade, storage: *mut CvMemStorage,
     scale_factor: c_double, min_neighbors: c_int, flags: c_int,
     min_size: CvSize, max_size: CvSize) -> *mut CvSeq;
-}
\ No newline at end of file
+  pub fn cvReleaseHaarClassifierCascade(cascade: *mut *mut CvHaarClassifierCascade);
+}
+
+#[cfg(opencv_4)]
+extern "C-unwind" {
+  #[link_name = "cvrs_load_cascade"]
+  pub fn cvLoadHaarClassifierCascade(filename: *const c_char) -> *mut CvHaarClassifierCascade;
+  #[link_name = "cvrs_detect_multi_scale"]
+  pub fn cvHaarDetectObjects(image: *const CvArr, cascade: *mut CvHaarClassifierCascade, storage: *mut CvMemStorage,
+    scale_factor: c_double, min_neighbors: c_int, flags: c_int,
+    min_size: CvSize, max_size: CvSize) -> *mut CvSeq;
+  #[link_name = "cvrs_release_cascade"]
+  pub fn cvReleaseHaarClassifierCascade(cascade: *mut *mut CvHaarClassifierCascade);
+}
@@ -1,10 +1,22 @@
-use libc::{c_double, c_int};
+use libc::{c_char, c_double, c_float, c_int, c_schar, c_void};
 
 #[repr(C)]
 pub struct CvArr;
 
 #[repr(C)]
-pub struct CvBox2D;
+#[derive(Clone, Copy)]
+pub struct CvAttrList {
+  pub attr: *const *const c_char,
+  pub next: *mut CvAttrList,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CvBox2D {
+  pub center: CvPoint2D32f,
+  pub size: CvSize2D32f,
+  pub angle: c_float,
+}
 
 #[repr(C)]
 pub struct CvCapture;
@@ -16,16 +28,76 @@ pub struct CvChain;
 pub struct CvChainPtReader;
 
 #[repr(C)]
-pub struct CvConnectedComp;
+#[derive(Clone, Copy)]
+pub struct CvConnectedComp {
+  pub area: c_double,
+  pub value: CvScalar,
+  pub rect: CvRect,
+  pub contour: *mut CvSeq,
+}
 
 #[repr(C)]
-pub struct CvContour;
+pub struct CvContour {
+  pub seq: CvSeq,
+  pub rect: CvRect,
+  pub color: c_int,
+  pub reserved: [c_int;3],
+}
 
 #[repr(C)]
 pub struct CvContourScanner;
 
 #[repr(C)]
-pub struct CvFont;
+pub struct CvConvexityDefect {
+  pub start: *mut CvPoint,
+  pub end: *mut CvPoint,
+  pub depth_point: *mut CvPoint,
+  pub depth: c_float,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CvFont {
+  pub name_font: *const c_char,
+  pub color: CvScalar,
+  pub font_face: c_int,
+  pub ascii: *const c_int,
+  pub greek: *const c_int,
+  pub cyrillic: *const c_int,
+  pub hscale: c_float,
+  pub vscale: c_float,
+  pub shear: c_float,
+  pub thickness: c_int,
+  pub dx: c_float,
+  pub line_type: c_int,
+}
+
+#[repr(C)]
+pub struct CvFileMapNode {
+  pub value: CvFileNode,
+  pub key: *const CvStringHashNode,
+  pub next: *mut CvFileMapNode,
+}
+
+#[repr(C)]
+pub struct CvFileNode {
+  pub tag: c_int,
+  pub info: *mut c_void,
+  pub data: CvFileNodeData,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union CvFileNodeData {
+  pub f: c_double,
+  pub i: c_int,
+  pub str_: CvString,
+  pub seq: *mut CvSeq,
+  pub map: *mut CvSeq,
+}
+
+#[repr(C)]
+pub struct CvFileStorage;
 
 #[repr(C)]
 pub struct CvHaarClassifierCascade;
@@ -34,30 +106,100 @@ pub struct CvHaarClassifierCascade;
 pub struct CvHistogram;
 
 #[repr(C)]
-pub struct CvHuMoments;
+#[derive(Clone, Copy)]
+pub struct CvHuMoments {
+  pub hu1: c_double,
+  pub hu2: c_double,
+  pub hu3: c_double,
+  pub hu4: c_double,
+  pub hu5: c_double,
+  pub hu6: c_double,
+  pub hu7: c_double,
+}
+
+// cv::KeyPoint, which has no C counterpart; the features2d shim stores it in
+// sequences as is.
+#[cfg(feature = "features2d")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CvKeyPoint {
+  pub pt: CvPoint2D32f,
+  pub size: c_float,
+  pub angle: c_float,
+  pub response: c_float,
+  pub octave: c_int,
+  pub class_id: c_int,
+}
 
 #[repr(C)]
 pub struct CvLineIterator;
 
 #[repr(C)]
-pub struct CvMat;
+pub struct CvMat {
+  pub type_: c_int,
+  pub step: c_int,
+  pub refcount: *mut c_int,
+  pub hdr_refcount: c_int,
+  pub data: *mut u8,
+  pub rows: c_int,
+  pub cols: c_int,
+}
+
+#[repr(C)]
+pub struct CvMemBlock {
+  pub prev: *mut CvMemBlock,
+  pub next: *mut CvMemBlock,
+}
 
 #[repr(C)]
-pub struct CvMemStorage;
+pub struct CvMemStorage {
+  pub signature: c_int,
+  pub bottom: *mut CvMemBlock,
+  pub top: *mut CvMemBlock,
+  pub parent: *mut CvMemStorage,
+  pub block_size: c_int,
+  pub free_space: c_int,
+}
 
 #[repr(C)]
-pub struct CvMoments;
+#[derive(Clone, Copy)]
+pub struct CvMoments {
+  pub m00: c_double,
+  pub m10: c_double,
+  pub m01: c_double,
+  pub m20: c_double,
+  pub m11: c_double,
+  pub m02: c_double,
+  pub m30: c_double,
+  pub m21: c_double,
+  pub m12: c_double,
+  pub m03: c_double,
+  pub mu20: c_double,
+  pub mu11: c_double,
+  pub mu02: c_double,
+  pub mu30: c_double,
+  pub mu21: c_double,
+  pub mu12: c_double,
+  pub mu03: c_double,
+  pub inv_sqrt_m00: c_double,
+}
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct CvPoint {
   pub x: c_int,
   pub y: c_int,
 }
 
 #[repr(C)]
-pub struct CvPoint2D32f;
+#[derive(Clone, Copy)]
+pub struct CvPoint2D32f {
+  pub x: c_float,
+  pub y: c_float,
+}
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct CvRect {
   pub x: c_int,
   pub y: c_int,
@@ -66,7 +208,10 @@ pub struct CvRect {
 }
 
 #[repr(C)]
-pub type CvScalar = [c_double;4];
+#[derive(Clone, Copy)]
+pub struct CvScalar {
+  pub val: [c_double;4],
+}
 
 #[repr(C)]
 pub struct CvSeq {
@@ -77,22 +222,66 @@ pub struct CvSeq {
   pub v_prev: *mut CvSeq,
   pub v_next: *mut CvSeq,
   pub total: c_int,
+  pub elem_size: c_int,
+  pub block_max: *mut c_schar,
+  pub ptr: *mut c_schar,
+  pub delta_elems: c_int,
+  pub storage: *mut CvMemStorage,
+  pub free_blocks: *mut CvSeqBlock,
+  pub first: *mut CvSeqBlock,
 }
 
 #[repr(C)]
-pub struct CvSeqBlock;
+pub struct CvSeqBlock {
+  pub prev: *mut CvSeqBlock,
+  pub next: *mut CvSeqBlock,
+  pub start_index: c_int,
+  pub count: c_int,
+  pub data: *mut c_schar,
+}
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct CvSize {
   pub width: c_int,
   pub height: c_int,
 }
 
 #[repr(C)]
-pub struct CvSlice;
+#[derive(Clone, Copy)]
+pub struct CvSize2D32f {
+  pub width: c_float,
+  pub height: c_float,
+}
 
 #[repr(C)]
-pub struct CvTermCriteria;
+#[derive(Clone, Copy)]
+pub struct CvSlice {
+  pub start_index: c_int,
+  pub end_index: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CvTermCriteria {
+  pub type_: c_int,
+  pub max_iter: c_int,
+  pub epsilon: c_double,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CvString {
+  pub len: c_int,
+  pub ptr: *mut c_char,
+}
+
+#[repr(C)]
+pub struct CvStringHashNode {
+  pub hashval: u32,
+  pub str_: CvString,
+  pub next: *mut CvStringHashNode,
+}
 
 #[repr(C)]
 pub struct CvVideoWriter;
@@ -101,4 +290,40 @@ pub struct CvVideoWriter;
 pub struct IplConvKernel;
 
 #[repr(C)]
-pub struct IplImage;
+pub struct IplImage {
+  pub n_size: c_int,
+  pub id: c_int,
+  pub n_channels: c_int,
+  pub alpha_channel: c_int,
+  pub depth: c_int,
+  pub color_model: [c_char;4],
+  pub channel_seq: [c_char;4],
+  pub data_order: c_int,
+  pub origin: c_int,
+  pub align: c_int,
+  pub width: c_int,
+  pub height: c_int,
+  pub roi: *mut IplROI,
+  pub mask_roi: *mut IplImage,
+  pub image_id: *mut c_void,
+  pub tile_info: *mut c_void,
+  pub image_size: c_int,
+  pub image_data: *mut c_char,
+  pub width_step: c_int,
+  pub border_mode: [c_int;4],
+  pub border_const: [c_int;4],
+  pub image_data_origin: *mut c_char,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct IplROI {
+  pub coi: c_int,
+  pub x_offset: c_int,
+  pub y_offset: c_int,
+  pub width: c_int,
+  pub height: c_int,
+}
+
+// Generated by build.rs from the same table it checks the headers against.
+include!(concat!(env!("OUT_DIR"), "/layout.rs"));
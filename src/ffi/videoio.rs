@@ -1,23 +1,38 @@
 use libc::{c_char, c_double, c_int};
 use ffi::types::{CvCapture, CvSize, CvVideoWriter, IplImage};
 
-#[cfg(feature = "opencv24")]
-#[link(name = "opencv_video")]
-extern {}
-
-#[cfg(feature = "opencv30")]
-#[link(name = "opencv_videoio")]
-extern {}
+#[cfg(not(opencv_4))]
+extern "C-unwind" {
+  pub fn cvCreateFileCapture(filename: *const c_char) -> *const CvCapture;
+  pub fn cvCreateCameraCapture(index: c_int) -> *const CvCapture;
+  pub fn cvQueryFrame(capture: *const CvCapture) -> *const IplImage;
+  pub fn cvReleaseCapture(capture: *const *const CvCapture);
+  pub fn cvGetCaptureProperty(capture: *const CvCapture, property_id: c_int) -> c_double;
+  pub fn cvSetCaptureProperty(capture: *const CvCapture, property_id: c_int, value: c_double) -> c_int;
+  pub fn cvCreateVideoWriter(filename: *const c_char, fourcc: c_int, fps: c_double, frame_size: CvSize, is_color: c_int) -> *const CvVideoWriter;
+  pub fn cvWriteFrame(writer: *const CvVideoWriter, image: *const IplImage) -> c_int;
+  pub fn cvReleaseVideoWriter(writer: *const *const CvVideoWriter);
+}
 
-extern "C" {
+#[cfg(opencv_4)]
+extern "C-unwind" {
+  #[link_name = "cvrs_create_file_capture"]
   pub fn cvCreateFileCapture(filename: *const c_char) -> *const CvCapture;
+  #[link_name = "cvrs_create_camera_capture"]
   pub fn cvCreateCameraCapture(index: c_int) -> *const CvCapture;
+  #[link_name = "cvrs_query_frame"]
   pub fn cvQueryFrame(capture: *const CvCapture) -> *const IplImage;
+  #[link_name = "cvrs_release_capture"]
   pub fn cvReleaseCapture(capture: *const *const CvCapture);
+  #[link_name = "cvrs_get_capture_property"]
   pub fn cvGetCaptureProperty(capture: *const CvCapture, property_id: c_int) -> c_double;
+  #[link_name = "cvrs_set_capture_property"]
   pub fn cvSetCaptureProperty(capture: *const CvCapture, property_id: c_int, value: c_double) -> c_int;
+  #[link_name = "cvrs_create_video_writer"]
   pub fn cvCreateVideoWriter(filename: *const c_char, fourcc: c_int, fps: c_double, frame_size: CvSize, is_color: c_int) -> *const CvVideoWriter;
+  #[link_name = "cvrs_write_frame"]
   pub fn cvWriteFrame(writer: *const CvVideoWriter, image: *const IplImage) -> c_int;
+  #[link_name = "cvrs_release_video_writer"]
   pub fn cvReleaseVideoWriter(writer: *const *const CvVideoWriter);
 }
 
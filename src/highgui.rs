@@ -1,12 +1,13 @@
 use std::{mem, ptr};
 use libc::{c_int, c_void};
 use ffi::highgui::*;
+use error::{Result, guard};
 use image::Image;
 use std::ffi::CString;
 use num::FromPrimitive;
 
-pub fn wait_key(delay: i32) -> i32 {
-  unsafe { cvWaitKey(delay) as i32 }
+pub fn wait_key(delay: i32) -> Result<i32> {
+  guard(|| unsafe { cvWaitKey(delay) as i32 })
 }
 
 pub struct Trackbar {
@@ -16,20 +17,20 @@ pub struct Trackbar {
 }
 
 impl Trackbar {
-  pub fn position(&self) -> u32 {
+  pub fn position(&self) -> Result<u32> {
     let trackbar_name = CString::new(self.name.as_bytes()).unwrap();
     let window_name = CString::new(self.window.as_bytes()).unwrap();
-    unsafe {
+    guard(|| unsafe {
       cvGetTrackbarPos(trackbar_name.as_ptr(), window_name.as_ptr()) as u32
-    }
+    })
   }
 
-  pub fn set_position(&mut self, position: u32) {
+  pub fn set_position(&mut self, position: u32) -> Result<()> {
     let trackbar_name = CString::new(self.name.as_bytes()).unwrap();
     let window_name = CString::new(self.window.as_bytes()).unwrap();
-    unsafe {
+    guard(|| unsafe {
       cvSetTrackbarPos(trackbar_name.as_ptr(), window_name.as_ptr(), position as i32);
-    }
+    })
   }
 }
 
@@ -63,36 +64,36 @@ pub struct Window {
 }
 
 impl Window {
-  pub fn named(name: &str) -> Window {
+  pub fn named(name: &str) -> Result<Window> {
     let name_c_str = CString::new(name.as_bytes()).unwrap();
-    unsafe {
+    guard(|| unsafe {
       cvNamedWindow(name_c_str.as_ptr(), 1i32);
-    };
-    Window { name: name.to_string(), trackbars: Vec::new(), on_mouse: None }
+    })?;
+    Ok(Window { name: name.to_string(), trackbars: Vec::new(), on_mouse: None })
   }
 
-  pub fn show(&self, image: &Image) {
+  pub fn show(&self, image: &Image) -> Result<()> {
     let name_c_str = CString::new(self.name.as_bytes()).unwrap();
-    unsafe {
-      cvShowImage(name_c_str.as_ptr(), image.raw);
-    }
+    guard(|| unsafe {
+      cvShowImage(name_c_str.as_ptr(), image.as_raw());
+    })
   }
 
-  pub fn move_(&self, x: u32, y: u32) {
+  pub fn move_(&self, x: u32, y: u32) -> Result<()> {
     let name_c_str = CString::new(self.name.as_bytes()).unwrap();
-    unsafe {
+    guard(|| unsafe {
       cvMoveWindow(name_c_str.as_ptr(), x as i32, y as i32);
-    }
+    })
   }
 
-  pub fn resize(&self, width: u32, height: u32) {
+  pub fn resize(&self, width: u32, height: u32) -> Result<()> {
     let name_c_str = CString::new(self.name.as_bytes()).unwrap();
-    unsafe {
+    guard(|| unsafe {
       cvResizeWindow(name_c_str.as_ptr(), width as i32, height as i32);
-    }
+    })
   }
 
-  pub fn create_trackbar(&mut self, name: &str, position: u32, max: u32, on_change: fn(u32)) -> Trackbar {
+  pub fn create_trackbar(&mut self, name: &str, position: u32, max: u32, on_change: fn(u32)) -> Result<Trackbar> {
     extern "C" fn wrapper(pos: c_int, userdata: *const c_void) {
       let callback = unsafe { mem::transmute::<_, &mut fn(u32)>(userdata) };
 
@@ -105,15 +106,15 @@ impl Window {
     let trackbar_name = CString::new(trackbar.name.as_bytes()).unwrap();
     let window_name = CString::new(trackbar.window.as_bytes()).unwrap();
 
-    unsafe {
+    guard(|| unsafe {
       cvCreateTrackbar2(trackbar_name.as_ptr(), window_name.as_ptr(), ptr::null(), max as i32, wrapper, mem::transmute(trackbar.on_change.as_ref().unwrap()));
       cvSetTrackbarPos(trackbar_name.as_ptr(), window_name.as_ptr(), position as i32);
-    }
+    })?;
 
-    trackbar.clone()
+    Ok(trackbar.clone())
   }
 
-  pub fn on_mouse(&mut self, on_mouse: fn(MouseEvent, i32, i32)) {
+  pub fn on_mouse(&mut self, on_mouse: fn(MouseEvent, i32, i32)) -> Result<()> {
     extern "C" fn wrapper(event: c_int, x: c_int, y: c_int, _: c_int, param: *const c_void) {
       let event = FromPrimitive::from_i32(event).unwrap();
       let callback = unsafe { mem::transmute::<_, &mut fn(MouseEvent, i32, i32)>(param) };
@@ -123,17 +124,18 @@ impl Window {
 
     self.on_mouse = Some(on_mouse);
     let name_c_str = CString::new(self.name.as_bytes()).unwrap();
-    unsafe {
+    guard(|| unsafe {
       cvSetMouseCallback(name_c_str.as_ptr(), wrapper, mem::transmute(self.on_mouse.as_ref().unwrap()));
-    }
+    })
   }
 }
 
 impl Drop for Window {
   fn drop(&mut self) {
     let name_c_str = CString::new(self.name.as_bytes()).unwrap();
-    unsafe {
+    // Nothing can be reported from a destructor, but the exception must not escape.
+    let _ = guard(|| unsafe {
       cvDestroyWindow(name_c_str.as_ptr());
-    };
+    });
   }
 }
@@ -1,32 +1,174 @@
+#[cfg(feature = "imgproc")]
+use std::f64::consts::PI;
+#[cfg(feature = "imgproc")]
+use std::mem;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::Deref;
 use std::ptr;
+#[cfg(feature = "image")]
+use std::slice;
+use libc::c_int;
 use ffi::core::*;
-use ffi::highgui::*;
+use ffi::imgcodecs::*;
+#[cfg(feature = "imgproc")]
 use ffi::imgproc::*;
-use ffi::types::{CvArr, CvPoint, CvRect, CvSize, IplImage};
-use core::{Color, Point, Rect, Size, as_c_str};
+use ffi::types::{CvArr, CvSize, IplImage};
+#[cfg(feature = "imgproc")]
+use ffi::types::{CvContour, CvMat, CvPoint, CvRect, CvScalar, CvSeq};
+use arithm::arr;
+use core::{Size, as_c_str};
+#[cfg(feature = "imgproc")]
+use core::{Color, MemStorage, Point, Point2f, Rect, Seq, TermCriteria};
+use error::{Error, Result, guard};
+#[cfg(feature = "imgproc")]
+use mat::Mat;
 
 use std::path::Path;
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Depth {
+  U8,
+  S8,
+  U16,
+  S16,
+  S32,
+  F32,
+  F64,
+}
+
+impl Depth {
+  pub(crate) fn to_ipl(self) -> c_int {
+    match self {
+      Depth::U8 => 8,
+      Depth::S8 => 0x80000008u32 as c_int,
+      Depth::U16 => 16,
+      Depth::S16 => 0x80000010u32 as c_int,
+      Depth::S32 => 0x80000020u32 as c_int,
+      Depth::F32 => 32,
+      Depth::F64 => 64,
+    }
+  }
+
+  pub(crate) fn from_ipl(depth: c_int) -> Option<Depth> {
+    [Depth::U8, Depth::S8, Depth::U16, Depth::S16, Depth::S32, Depth::F32, Depth::F64].iter()
+      .find(|d| d.to_ipl() == depth).cloned()
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ContourMode {
+  External = 0,
+  List = 1,
+  CComp = 2,
+  Tree = 3,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ContourApproximation {
+  None = 1,
+  Simple = 2,
+  TehChinL1 = 3,
+  TehChinKCos = 4,
+}
+
+// A forward transform of a real image yields either the packed CCS layout of
+// the same shape, or the full complex spectrum as a 2-channel image.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Spectrum {
+  Packed,
+  Complex,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FlipMode {
+  Horizontal,
+  Vertical,
+  Both,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Rotation {
+  Clockwise90,
+  CounterClockwise90,
+  Rotate180,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ColorMap {
+  Autumn,
+  Bone,
+  Cool,
+  Hot,
+  Hsv,
+  Inferno,
+  Jet,
+  Magma,
+  Ocean,
+  Rainbow,
+  Spring,
+  Summer,
+  Viridis,
+  Winter,
+}
+
 pub struct Image {
-  pub raw: *const IplImage,
-  pub is_owned: bool,
+  raw: *const IplImage,
+  is_owned: bool,
 }
 
 impl Image {
-  pub fn load(path: &Path) -> Result<Image, String> {
+  pub fn load(path: &Path) -> Result<Image> {
     let path_c_str = as_c_str(path);
-    unsafe {
-      match cvLoadImage(path_c_str.as_ptr(), 1) { // CV_LOAD_IMAGE_COLOR
-        p if !p.is_null() => Ok(Image { raw: p, is_owned: true }),
-        _ => Err(String::from_utf8_lossy(path_c_str.to_bytes()).into_owned()),
-      }
+    match guard(|| unsafe { cvLoadImage(path_c_str.as_ptr(), 1) })? { // CV_LOAD_IMAGE_COLOR
+      p if !p.is_null() => Ok(Image { raw: p, is_owned: true }),
+      _ => Err(Error::from_path(path)),
+    }
+  }
+
+  pub fn new(size: &Size, depth: Depth, channels: u32) -> Result<Image> {
+    if size.width <= 0 || size.height <= 0 {
+      return Err(Error::BadArgument(format!("invalid image size {}x{}", size.width, size.height)));
+    }
+    if channels < 1 || channels > 4 {
+      return Err(Error::BadArgument(format!("images have 1 to 4 channels, got {}", channels)));
+    }
+    let cv_size = CvSize { width: size.width as i32, height: size.height as i32 };
+    match guard(|| unsafe { cvCreateImage(cv_size, depth.to_ipl(), channels as c_int) })? {
+      p if !p.is_null() => {
+        let image = Image { raw: p, is_owned: true };
+        guard(|| unsafe { cvSetZero(p as *const CvArr) })?;
+        Ok(image)
+      },
+      _ => Err(Error::BadArgument(format!("could not allocate a {}x{} image", size.width, size.height))),
     }
   }
 
-  pub fn save(&self, path: &Path) -> bool {
+  /// Takes ownership of an image from the C API.
+  ///
+  /// # Safety
+  ///
+  /// `raw` must be a valid, non-null image allocated by OpenCV, such as one
+  /// from `cvCreateImage` or `cvLoadImage`, that nothing else releases. The
+  /// returned `Image` frees it with `cvReleaseImage`.
+  pub unsafe fn from_raw(raw: *const IplImage) -> Image {
+    Image { raw: raw, is_owned: true }
+  }
+
+  #[cfg(feature = "video")]
+  pub(crate) unsafe fn borrowed(raw: *const IplImage) -> Image {
+    Image { raw: raw, is_owned: false }
+  }
+
+  pub fn as_raw(&self) -> *const IplImage {
+    self.raw
+  }
+
+  pub fn save(&self, path: &Path) -> Result<()> {
     let path_c_str = as_c_str(path);
-    unsafe {
-      cvSaveImage(path_c_str.as_ptr(), self.raw, ptr::null()) == 0
+    match guard(|| unsafe { cvSaveImage(path_c_str.as_ptr(), self.raw, ptr::null()) }) {
+      Ok(0) | Err(_) => Err(Error::from_output_path(path, path.display().to_string())),
+      Ok(_) => Ok(()),
     }
   }
 
@@ -40,74 +182,281 @@ impl Image {
   pub fn width(&self) -> i32 { self.size().width }
   pub fn height(&self) -> i32 { self.size().height }
 
-  pub fn add_line(&mut self, p1: &Point, p2: &Point, color: &Color, thickness: u32) {
+  // Headers from `from_raw` may carry depths with no `Depth`, such as IPL_DEPTH_1U.
+  pub fn depth(&self) -> Result<Depth> {
+    let depth = unsafe { (*self.raw).depth };
+    Depth::from_ipl(depth).ok_or_else(|| Error::UnsupportedFormat(format!("IplImage depth {:#x}", depth)))
+  }
+
+  pub fn channels(&self) -> u32 {
+    unsafe { (*self.raw).n_channels as u32 }
+  }
+}
+
+fn alternatives<T: fmt::Debug>(values: &[T]) -> String {
+  values.iter().map(|v| format!("{:?}", v)).collect::<Vec<_>>().join(" or ")
+}
+
+// Argument checks and output allocation shared by the wrappers over the C API.
+impl Image {
+  pub(crate) fn expect_depth(&self, depths: &[Depth], func: &str) -> Result<()> {
+    let depth = self.depth()?;
+    if !depths.contains(&depth) {
+      return Err(Error::BadArgument(format!("{} needs {} data, got {:?}", func, alternatives(depths), depth)));
+    }
+    Ok(())
+  }
+
+  pub(crate) fn expect_channels(&self, channels: &[u32], func: &str) -> Result<()> {
+    if !channels.contains(&self.channels()) {
+      return Err(Error::BadArgument(format!("{} needs {} channels, got {}", func, alternatives(channels), self.channels())));
+    }
+    Ok(())
+  }
+
+  // Multi-channel images qualify once a channel of interest is selected.
+  pub(crate) fn expect_single_channel(&self, func: &str) -> Result<()> {
+    if self.channels() != 1 && self.coi().is_none() {
+      return Err(Error::BadArgument(format!("{} needs a single-channel image or a channel of interest, got {} channels", func, self.channels())));
+    }
+    Ok(())
+  }
+
+  pub(crate) fn expect_channel_index(&self, index: u32) -> Result<()> {
+    if index >= self.channels() {
+      return Err(Error::BadArgument(format!("channel {} is out of range for {} channels", index, self.channels())));
+    }
+    Ok(())
+  }
+
+  pub(crate) fn expect_same_shape(&self, other: &Image, func: &str) -> Result<()> {
+    let (depth, other_depth) = (self.depth()?, other.depth()?);
+    if self.size() != other.size() || depth != other_depth || self.channels() != other.channels() {
+      return Err(Error::BadArgument(format!("{} needs images of the same shape, got {}x{}x{} {:?} and {}x{}x{} {:?}", func,
+        self.width(), self.height(), self.channels(), depth,
+        other.width(), other.height(), other.channels(), other_depth)));
+    }
+    Ok(())
+  }
+
+  pub(crate) fn expect_mask(&self, mask: Option<&Mask>, func: &str) -> Result<()> {
+    match mask {
+      Some(mask) if mask.size() != self.size() =>
+        Err(Error::BadArgument(format!("{} needs a {}x{} mask, got {}x{}", func, self.width(), self.height(), mask.width(), mask.height()))),
+      _ => Ok(()),
+    }
+  }
+
+  // Allocates an output image and lets a native call fill it.
+  pub(crate) fn new_with<F: FnOnce(*const CvArr)>(size: &Size, depth: Depth, channels: u32, f: F) -> Result<Image> {
+    let dst = Image::new(size, depth, channels)?;
+    guard(|| f(arr(&dst)))?;
+    Ok(dst)
+  }
+
+  // Runs `f(src, dst)` into an output shaped like `self`.
+  pub(crate) fn unary<F: FnOnce(*const CvArr, *const CvArr)>(&self, f: F) -> Result<Image> {
+    Image::new_with(&self.size(), self.depth()?, self.channels(), |dst| f(arr(self), dst))
+  }
+
+  // Runs `f(src1, src2, dst)` on two images of the same shape; `f` passes the
+  // mask on when the native call supports one.
+  pub(crate) fn binary<F: FnOnce(*const CvArr, *const CvArr, *const CvArr)>(&self, other: &Image, mask: Option<&Mask>, func: &str, f: F) -> Result<Image> {
+    self.expect_same_shape(other, func)?;
+    self.expect_mask(mask, func)?;
+    self.unary(|src, dst| f(src, arr(other), dst))
+  }
+}
+
+#[cfg(feature = "imgproc")]
+impl Image {
+  pub fn filter_2d(&self, kernel: &Mat<f32>, anchor: Option<&Point>) -> Result<Image> {
+    let anchor = anchor.map_or(CvPoint { x: -1, y: -1 }, |p| CvPoint { x: p.x as i32, y: p.y as i32 });
+    self.unary(|src, dst| unsafe { cvFilter2D(src, dst, kernel.as_raw(), anchor) })
+  }
+
+  pub fn warp_affine(&self, map_matrix: &Mat<f64>, size: &Size) -> Result<Image> {
+    if map_matrix.rows() != 2 || map_matrix.cols() != 3 {
+      return Err(Error::BadArgument(format!("affine transforms are 2x3, got {}x{}", map_matrix.rows(), map_matrix.cols())));
+    }
+    self.warp(map_matrix.as_raw(), size, cvWarpAffine)
+  }
+
+  pub fn warp_perspective(&self, map_matrix: &Mat<f64>, size: &Size) -> Result<Image> {
+    if map_matrix.rows() != 3 || map_matrix.cols() != 3 {
+      return Err(Error::BadArgument(format!("perspective transforms are 3x3, got {}x{}", map_matrix.rows(), map_matrix.cols())));
+    }
+    self.warp(map_matrix.as_raw(), size, cvWarpPerspective)
+  }
+
+  fn warp(&self, map_matrix: *const CvMat, size: &Size,
+    f: unsafe extern "C-unwind" fn(*const CvArr, *const CvArr, *const CvMat, c_int, CvScalar)) -> Result<Image> {
+
+    Image::new_with(size, self.depth()?, self.channels(), |dst| unsafe {
+      f(arr(self), dst, map_matrix, 1 + 8, CvScalar { val: [0.0;4] }); // CV_INTER_LINEAR + CV_WARP_FILL_OUTLIERS
+    })
+  }
+
+  #[cfg(any(not(opencv_4), feature = "calib3d"))]
+  pub fn undistort(&self, camera_matrix: &Mat<f64>, distortion_coeffs: &Mat<f64>) -> Result<Image> {
+    if camera_matrix.rows() != 3 || camera_matrix.cols() != 3 {
+      return Err(Error::BadArgument(format!("camera matrices are 3x3, got {}x{}", camera_matrix.rows(), camera_matrix.cols())));
+    }
+    self.unary(|src, dst| unsafe { cvUndistort2(src, dst, camera_matrix.as_raw(), distortion_coeffs.as_raw(), ptr::null()) })
+  }
+
+  pub fn find_contours<'s>(&mut self, storage: &'s MemStorage, mode: ContourMode, method: ContourApproximation) -> Result<Option<Seq<'s, Point>>> {
+    self.expect_depth(&[Depth::U8], "cvFindContours")?;
+    self.expect_channels(&[1], "cvFindContours")?;
+    let mut first = ptr::null_mut();
+    guard(|| unsafe {
+      cvFindContours(self.raw as *const CvArr, storage.as_raw(), &mut first, mem::size_of::<CvContour>() as i32,
+        mode as i32, method as i32, CvPoint { x: 0, y: 0 });
+    })?;
+    Ok(unsafe { Seq::from_raw(first) })
+  }
+
+  pub fn hough_lines<'s>(&self, storage: &'s MemStorage, rho: f64, theta: f64, threshold: u32) -> Result<Seq<'s, [f32;2]>> {
+    self.expect_depth(&[Depth::U8], "cvHoughLines2")?;
+    self.expect_channels(&[1], "cvHoughLines2")?;
+    let lines = guard(|| unsafe {
+      cvHoughLines2(self.raw as *const CvArr, storage.as_raw() as *const _, 0, rho, theta, threshold as i32, 0.0, 0.0, 0.0, PI) // CV_HOUGH_STANDARD
+    })?;
+    Ok(unsafe { Seq::from_raw(lines as *mut CvSeq) }.expect("cvHoughLines2 returned no sequence"))
+  }
+
+  pub fn hough_lines_p<'s>(&self, storage: &'s MemStorage, rho: f64, theta: f64, threshold: u32, min_length: f64, max_gap: f64) -> Result<Seq<'s, [Point;2]>> {
+    self.expect_depth(&[Depth::U8], "cvHoughLines2")?;
+    self.expect_channels(&[1], "cvHoughLines2")?;
+    let lines = guard(|| unsafe {
+      cvHoughLines2(self.raw as *const CvArr, storage.as_raw() as *const _, 1, rho, theta, threshold as i32, min_length, max_gap, 0.0, PI) // CV_HOUGH_PROBABILISTIC
+    })?;
+    Ok(unsafe { Seq::from_raw(lines as *mut CvSeq) }.expect("cvHoughLines2 returned no sequence"))
+  }
+
+  pub fn hough_circles<'s>(&self, storage: &'s MemStorage, dp: f64, min_dist: f64, canny_threshold: f64, accumulator_threshold: f64, min_radius: u32, max_radius: u32) -> Result<Seq<'s, [f32;3]>> {
+    self.expect_depth(&[Depth::U8], "cvHoughCircles")?;
+    self.expect_channels(&[1], "cvHoughCircles")?;
+    let circles = guard(|| unsafe {
+      cvHoughCircles(self.raw as *const CvArr, storage.as_raw() as *const _, 3, dp, min_dist, canny_threshold, accumulator_threshold, min_radius as i32, max_radius as i32) // CV_HOUGH_GRADIENT
+    })?;
+    Ok(unsafe { Seq::from_raw(circles as *mut CvSeq) }.expect("cvHoughCircles returned no sequence"))
+  }
+
+  pub fn find_corner_sub_pix(&self, corners: &mut [Point2f], win: &Size, zero_zone: &Size, criteria: &TermCriteria) -> Result<()> {
+    self.expect_channels(&[1], "cvFindCornerSubPix")?;
+    let mut raw_corners = corners.iter().map(|p| p.to_raw()).collect::<Vec<_>>();
+    let win = CvSize { width: win.width as i32, height: win.height as i32 };
+    let zero_zone = CvSize { width: zero_zone.width as i32, height: zero_zone.height as i32 };
+    guard(|| unsafe {
+      cvFindCornerSubPix(self.raw as *const CvArr, raw_corners.as_mut_ptr(), raw_corners.len() as i32, win, zero_zone, criteria.to_raw());
+    })?;
+    for (corner, raw) in corners.iter_mut().zip(raw_corners.iter()) {
+      *corner = Point2f::from_raw(raw);
+    }
+    Ok(())
+  }
+
+  pub fn add_line(&mut self, p1: &Point, p2: &Point, color: &Color, thickness: u32) -> Result<()> {
     let p1 = CvPoint { x: p1.x as i32, y: p1.y as i32 };
     let p2 = CvPoint { x: p2.x as i32, y: p2.y as i32 };
-    unsafe {
-      cvLine(self.raw as *const CvArr, p1, p2, color.as_scalar(), thickness as i32, 16, 0); // CV_AA
-    }
+    guard(|| unsafe {
+      cvLine(self.raw as *const CvArr, p1, p2, CvScalar { val: color.as_scalar() }, thickness as i32, 16, 0); // CV_AA
+    })
   }
 
-  pub fn add_rectangle(&mut self, p1: &Point, p2: &Point, color: &Color, thickness: u32) {
+  pub fn add_rectangle(&mut self, p1: &Point, p2: &Point, color: &Color, thickness: u32) -> Result<()> {
     let p1 = CvPoint { x: p1.x as i32, y: p1.y as i32 };
     let p2 = CvPoint { x: p2.x as i32, y: p2.y as i32 };
-    unsafe {
-      cvRectangle(self.raw as *const CvArr, p1, p2, color.as_scalar(), thickness as i32, 16, 0); // CV_AA
-    }
+    guard(|| unsafe {
+      cvRectangle(self.raw as *const CvArr, p1, p2, CvScalar { val: color.as_scalar() }, thickness as i32, 16, 0); // CV_AA
+    })
   }
 
-  pub fn add_rectangle_r(&mut self, rect: &Rect, color: &Color, thickness: u32) {
+  pub fn add_rectangle_r(&mut self, rect: &Rect, color: &Color, thickness: u32) -> Result<()> {
     let rect = CvRect { x: rect.x as i32, y: rect.y as i32, width: rect.width as i32, height: rect.height as i32 };
-    unsafe {
-      cvRectangleR(self.raw as *const CvArr, rect, color.as_scalar(), thickness as i32, 16, 0); // CV_AA
-    }
+    guard(|| unsafe {
+      cvRectangleR(self.raw as *const CvArr, rect, CvScalar { val: color.as_scalar() }, thickness as i32, 16, 0); // CV_AA
+    })
   }
 
-  pub fn add_circle(&mut self, center: &Point, radius: u32, color: &Color, thickness: u32) {
+  pub fn add_circle(&mut self, center: &Point, radius: u32, color: &Color, thickness: u32) -> Result<()> {
     let center = CvPoint { x: center.x as i32, y: center.y as i32 };
-    unsafe {
-      cvCircle(self.raw as *const CvArr, center, radius as i32, color.as_scalar(), thickness as i32, 16, 0); // CV_AA
-    }
+    guard(|| unsafe {
+      cvCircle(self.raw as *const CvArr, center, radius as i32, CvScalar { val: color.as_scalar() }, thickness as i32, 16, 0); // CV_AA
+    })
   }
 
-  pub fn add_ellipse(&mut self, center: &Point, axes: &Size, angle: f64, start_angle: f64, end_angle: f64, color: &Color, thickness: u32) {
+  pub fn add_ellipse(&mut self, center: &Point, axes: &Size, angle: f64, start_angle: f64, end_angle: f64, color: &Color, thickness: u32) -> Result<()> {
     let center = CvPoint { x: center.x as i32, y: center.y as i32 };
     let axes = CvSize { width: axes.width as i32, height: axes.height as i32 };
-    unsafe {
-      cvEllipse(self.raw as *const CvArr, center, axes, angle, start_angle, end_angle, color.as_scalar(), thickness as i32, 16, 0); // CV_AA
-    }
+    guard(|| unsafe {
+      cvEllipse(self.raw as *const CvArr, center, axes, angle, start_angle, end_angle, CvScalar { val: color.as_scalar() }, thickness as i32, 16, 0); // CV_AA
+    })
   }
 
-  pub fn add_filled_convex_polygon(&mut self, points: &[&Point], color: &Color) {
+  pub fn add_filled_convex_polygon(&mut self, points: &[&Point], color: &Color) -> Result<()> {
     let count = points.len();
     let points =
       points.iter()
       .map(|p| {
         CvPoint { x: p.x as i32, y: p.y as i32 }
       })
-      .collect::<Vec<CvPoint>>().as_ptr();
-    unsafe {
-      cvFillConvexPoly(self.raw as *const CvArr, points, count as i32, color.as_scalar(), 16, 0); // CV_AA
-    }
+      .collect::<Vec<CvPoint>>();
+    guard(|| unsafe {
+      cvFillConvexPoly(self.raw as *const CvArr, points.as_ptr(), count as i32, CvScalar { val: color.as_scalar() }, 16, 0); // CV_AA
+    })
   }
 
-  pub fn add_filled_polygons(&mut self, polygons: &[&[&Point]], contours: u32, color: &Color) {
-    let counts = polygons.iter().map(|ps| ps.len() as i32).collect::<Vec<i32>>().as_ptr();
+  pub fn add_filled_polygons(&mut self, polygons: &[&[&Point]], contours: u32, color: &Color) -> Result<()> {
+    let counts = polygons.iter().map(|ps| ps.len() as i32).collect::<Vec<i32>>();
     let polygons =
       polygons.iter()
       .map(|ps| {
-        ps.iter().map(|p| CvPoint { x: p.x as i32, y: p.y as i32 }).collect::<Vec<CvPoint>>().as_ptr()
+        ps.iter().map(|p| CvPoint { x: p.x as i32, y: p.y as i32 }).collect::<Vec<CvPoint>>()
       })
-      .collect::<Vec<*const CvPoint>>().as_ptr();
-    unsafe {
-      cvFillPoly(self.raw as *const CvArr, polygons, counts, contours as i32, color.as_scalar(), 16, 0); // CV_AA
-    }
+      .collect::<Vec<Vec<CvPoint>>>();
+    let polygon_ptrs = polygons.iter().map(|ps| ps.as_ptr()).collect::<Vec<*const CvPoint>>();
+    guard(|| unsafe {
+      cvFillPoly(self.raw as *const CvArr, polygon_ptrs.as_ptr(), counts.as_ptr(), contours as i32, CvScalar { val: color.as_scalar() }, 16, 0); // CV_AA
+    })
+  }
+}
+
+#[cfg(any(feature = "image", feature = "ndarray"))]
+impl Image {
+  pub(crate) fn width_step(&self) -> usize {
+    unsafe { (*self.raw).width_step as usize }
+  }
+
+  pub(crate) fn data_ptr(&self) -> *mut u8 {
+    unsafe { (*self.raw).image_data as *mut u8 }
+  }
+
+  #[cfg(feature = "image")]
+  pub(crate) unsafe fn row_as<T>(&self, y: usize) -> &[T] {
+    let data = self.data_ptr().add(y * self.width_step());
+    slice::from_raw_parts(data as *const T, self.width() as usize * self.channels() as usize)
+  }
+
+  #[cfg(feature = "image")]
+  pub(crate) unsafe fn row_as_mut<T>(&mut self, y: usize) -> &mut [T] {
+    let data = self.data_ptr().add(y * self.width_step());
+    slice::from_raw_parts_mut(data as *mut T, self.width() as usize * self.channels() as usize)
   }
 }
 
+// Borrowed images only exist inside a `FrameRef`, which carries the
+// capture's lifetime; no IplImage is reachable from more than one owner.
+unsafe impl Send for Image {}
+// Every `&self` method only reads the header and pixels, including the
+// channel of interest; writes such as drawing and `set_coi` take `&mut self`.
+unsafe impl Sync for Image {}
+
 impl Clone for Image {
   fn clone(&self) -> Image {
-    unsafe { Image { raw: cvCloneImage(self.raw), is_owned: true } }
+    let raw = guard(|| unsafe { cvCloneImage(self.raw) }).unwrap_or_else(|e| panic!("cannot clone image: {}", e));
+    Image { raw: raw, is_owned: true }
   }
 }
 
@@ -116,3 +465,77 @@ impl Drop for Image {
     if self.is_owned { unsafe { cvReleaseImage(&self.raw); } }
   }
 }
+
+#[derive(Clone)]
+pub struct Mask(Image);
+
+impl Mask {
+  pub fn new(size: &Size) -> Result<Mask> {
+    Image::new(size, Depth::U8, 1).map(Mask)
+  }
+
+  pub fn into_image(self) -> Image {
+    self.0
+  }
+}
+
+impl TryFrom<Image> for Mask {
+  type Error = Error;
+  fn try_from(image: Image) -> Result<Mask> {
+    let depth = image.depth()?;
+    if depth != Depth::U8 || image.channels() != 1 {
+      return Err(Error::BadArgument(format!("masks are single-channel 8-bit images, got {} channels of {:?}", image.channels(), depth)));
+    }
+    Ok(Mask(image))
+  }
+}
+
+impl Deref for Mask {
+  type Target = Image;
+  fn deref(&self) -> &Image {
+    &self.0
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use core::Size;
+  use error::Error;
+  use ffi::types::IplImage;
+  use super::{Depth, Image};
+
+  // Builds an 8-bit image from tightly packed rows of interleaved samples.
+  pub(crate) fn image_u8(width: i32, channels: u32, samples: &[u8]) -> Image {
+    let row_len = width as usize * channels as usize;
+    let height = samples.len() / row_len;
+    let image = Image::new(&Size::new(width, height as i32), Depth::U8, channels).unwrap();
+    for (y, row) in samples.chunks(row_len).enumerate() {
+      unsafe {
+        let data = (*image.raw).image_data as *mut u8;
+        let step = (*image.raw).width_step as usize;
+        data.add(y * step).copy_from_nonoverlapping(row.as_ptr(), row_len);
+      }
+    }
+    image
+  }
+
+  pub(crate) fn samples_u8(image: &Image) -> Vec<u8> {
+    let row_len = image.width() as usize * image.channels() as usize;
+    (0..image.height() as usize).flat_map(|y| unsafe {
+      let data = (*image.raw).image_data as *const u8;
+      let step = (*image.raw).width_step as usize;
+      (0..row_len).map(move |x| *data.add(y * step + x))
+    }).collect()
+  }
+
+  #[test]
+  fn unknown_depths_are_reported() {
+    let image = Image::new(&Size::new(2, 2), Depth::U8, 1).unwrap();
+    assert_eq!(image.depth(), Ok(Depth::U8));
+    let raw = image.as_raw() as *mut IplImage;
+    unsafe { (*raw).depth = 1 }; // IPL_DEPTH_1U
+    assert_eq!(image.depth(), Err(Error::UnsupportedFormat("IplImage depth 0x1".to_string())));
+    assert!(image.expect_depth(&[Depth::U8], "test").is_err());
+    unsafe { (*raw).depth = 8 };
+  }
+}
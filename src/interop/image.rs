@@ -0,0 +1,207 @@
+use std::convert::TryFrom;
+use std::mem;
+use std::ops::Deref;
+use std::slice;
+use image_rs::{GrayImage, ImageBuffer, Luma, Pixel, Primitive, Rgb, RgbImage, Rgba, RgbaImage};
+use core::Size;
+use error::{Error, Result};
+use image::{Depth, Image};
+
+const RGB_TO_BGR: [usize;3] = [2, 1, 0];
+const RGBA_TO_BGRA: [usize;4] = [2, 1, 0, 3];
+const GRAY: [usize;1] = [0];
+
+fn from_buffer<T, P, C>(buffer: &ImageBuffer<P, C>, depth: Depth, order: &[usize]) -> Result<Image>
+  where T: Copy + 'static, P: Pixel<Subpixel = T>, C: Deref<Target = [T]> {
+
+  let (width, height) = buffer.dimensions();
+  let channels = order.len();
+  let mut image = Image::new(&Size::new(width as i32, height as i32), depth, channels as u32)?;
+  // The container may be longer than the image; anything past the last row is ignored.
+  let samples: &[T] = buffer;
+  let samples = &samples[..width as usize * height as usize * channels];
+  for (y, src) in samples.chunks(width as usize * channels).enumerate() {
+    let dst = unsafe { image.row_as_mut::<T>(y) };
+    for (d, s) in dst.chunks_mut(channels).zip(src.chunks(channels)) {
+      for (i, &o) in order.iter().enumerate() {
+        d[i] = s[o];
+      }
+    }
+  }
+  Ok(image)
+}
+
+fn to_buffer<T, P>(image: &Image, depth: Depth, order: &[usize]) -> Result<ImageBuffer<P, Vec<T>>>
+  where T: Copy + Default + 'static, P: Pixel<Subpixel = T> {
+
+  let channels = order.len();
+  let image_depth = image.depth()?;
+  if image_depth != depth || image.channels() as usize != channels {
+    return Err(Error::UnsupportedFormat(format!("{} channels of {:?} cannot be converted to {} channels of {:?}",
+      image.channels(), image_depth, channels, depth)));
+  }
+
+  let (width, height) = (image.width() as u32, image.height() as u32);
+  let mut samples = vec![T::default(); width as usize * height as usize * channels];
+  for (y, dst) in samples.chunks_mut(width as usize * channels).enumerate() {
+    let src = unsafe { image.row_as::<T>(y) };
+    for (d, s) in dst.chunks_mut(channels).zip(src.chunks(channels)) {
+      for (i, &o) in order.iter().enumerate() {
+        d[o] = s[i];
+      }
+    }
+  }
+  Ok(ImageBuffer::from_raw(width, height, samples).expect("buffer matches the image dimensions"))
+}
+
+fn borrow_luma<T: Primitive + 'static>(image: &Image, depth: Depth) -> Option<ImageBuffer<Luma<T>, &[T]>> {
+  let row_len = image.width() as usize * mem::size_of::<T>();
+  if image.depth() != Ok(depth) || image.channels() != 1 || image.width_step() != row_len {
+    return None;
+  }
+  let len = image.width() as usize * image.height() as usize;
+  let samples = unsafe { slice::from_raw_parts(image.data_ptr() as *const T, len) };
+  ImageBuffer::from_raw(image.width() as u32, image.height() as u32, samples)
+}
+
+impl Image {
+  pub fn as_luma8(&self) -> Option<ImageBuffer<Luma<u8>, &[u8]>> {
+    borrow_luma(self, Depth::U8)
+  }
+
+  pub fn as_luma16(&self) -> Option<ImageBuffer<Luma<u16>, &[u16]>> {
+    borrow_luma(self, Depth::U16)
+  }
+}
+
+impl<'a> TryFrom<&'a RgbImage> for Image {
+  type Error = Error;
+  fn try_from(buffer: &'a RgbImage) -> Result<Image> {
+    from_buffer(buffer, Depth::U8, &RGB_TO_BGR)
+  }
+}
+
+impl<'a> TryFrom<&'a RgbaImage> for Image {
+  type Error = Error;
+  fn try_from(buffer: &'a RgbaImage) -> Result<Image> {
+    from_buffer(buffer, Depth::U8, &RGBA_TO_BGRA)
+  }
+}
+
+impl<'a> TryFrom<&'a GrayImage> for Image {
+  type Error = Error;
+  fn try_from(buffer: &'a GrayImage) -> Result<Image> {
+    from_buffer(buffer, Depth::U8, &GRAY)
+  }
+}
+
+impl<'a> TryFrom<&'a ImageBuffer<Luma<u16>, Vec<u16>>> for Image {
+  type Error = Error;
+  fn try_from(buffer: &'a ImageBuffer<Luma<u16>, Vec<u16>>) -> Result<Image> {
+    from_buffer(buffer, Depth::U16, &GRAY)
+  }
+}
+
+impl<'a> TryFrom<&'a Image> for RgbImage {
+  type Error = Error;
+  fn try_from(image: &'a Image) -> Result<RgbImage> {
+    to_buffer::<u8, Rgb<u8>>(image, Depth::U8, &RGB_TO_BGR)
+  }
+}
+
+impl<'a> TryFrom<&'a Image> for RgbaImage {
+  type Error = Error;
+  fn try_from(image: &'a Image) -> Result<RgbaImage> {
+    to_buffer::<u8, Rgba<u8>>(image, Depth::U8, &RGBA_TO_BGRA)
+  }
+}
+
+impl<'a> TryFrom<&'a Image> for GrayImage {
+  type Error = Error;
+  fn try_from(image: &'a Image) -> Result<GrayImage> {
+    to_buffer::<u8, Luma<u8>>(image, Depth::U8, &GRAY)
+  }
+}
+
+impl<'a> TryFrom<&'a Image> for ImageBuffer<Luma<u16>, Vec<u16>> {
+  type Error = Error;
+  fn try_from(image: &'a Image) -> Result<ImageBuffer<Luma<u16>, Vec<u16>>> {
+    to_buffer::<u16, Luma<u16>>(image, Depth::U16, &GRAY)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::convert::TryFrom;
+  use image_rs::{GrayImage, ImageBuffer, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+  use image::Image;
+  use image::tests::{image_u8, samples_u8};
+
+  #[test]
+  fn rgb_round_trip_swaps_to_bgr() {
+    // Three RGB pixels fill 9 bytes of a row padded to 12.
+    let buffer = RgbImage::from_fn(3, 2, |x, y| Rgb([x as u8, y as u8, 10 + x as u8 + 3 * y as u8]));
+    let image = Image::try_from(&buffer).unwrap();
+    assert_eq!(image.width_step(), 12);
+    assert_eq!(samples_u8(&image), vec![
+      10, 0, 0, 11, 0, 1, 12, 0, 2,
+      13, 1, 0, 14, 1, 1, 15, 1, 2,
+    ]);
+    assert_eq!(RgbImage::try_from(&image).unwrap(), buffer);
+  }
+
+  #[test]
+  fn rgba_round_trip_keeps_alpha_last() {
+    let buffer = RgbaImage::from_fn(3, 1, |x, _| Rgba([1, 2, 3, 100 + x as u8]));
+    let image = Image::try_from(&buffer).unwrap();
+    assert_eq!(samples_u8(&image), vec![3, 2, 1, 100, 3, 2, 1, 101, 3, 2, 1, 102]);
+    let back = RgbaImage::try_from(&image).unwrap();
+    assert_eq!(back.get_pixel(2, 0), &Rgba([1, 2, 3, 102]));
+    assert_eq!(back, buffer);
+  }
+
+  #[test]
+  fn bgr_image_converts_to_rgb() {
+    let image = image_u8(3, 3, &[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    let buffer = RgbImage::try_from(&image).unwrap();
+    assert_eq!(buffer.get_pixel(0, 0), &Rgb([3, 2, 1]));
+    assert_eq!(buffer.get_pixel(2, 0), &Rgb([9, 8, 7]));
+  }
+
+  #[test]
+  fn luma8_round_trip_skips_row_padding() {
+    let buffer = GrayImage::from_fn(3, 3, |x, y| Luma([(10 * y + x) as u8]));
+    let image = Image::try_from(&buffer).unwrap();
+    assert_eq!(image.width_step(), 4);
+    assert_eq!(samples_u8(&image), vec![0, 1, 2, 10, 11, 12, 20, 21, 22]);
+    assert_eq!(GrayImage::try_from(&image).unwrap(), buffer);
+    // Padded rows cannot be borrowed as a tightly packed buffer.
+    assert!(image.as_luma8().is_none());
+  }
+
+  #[test]
+  fn luma8_borrows_unpadded_rows() {
+    let image = image_u8(4, 1, &[1, 2, 3, 4, 5, 6, 7, 8]);
+    let view = image.as_luma8().unwrap();
+    assert_eq!(view.get_pixel(3, 1), &Luma([8]));
+    assert!(image.as_luma16().is_none());
+  }
+
+  #[test]
+  fn luma16_round_trip() {
+    let buffer = ImageBuffer::<Luma<u16>, Vec<u16>>::from_fn(3, 2, |x, y| Luma([1000 * y as u16 + 300 * x as u16 + 7]));
+    let image = Image::try_from(&buffer).unwrap();
+    assert_eq!(image.width_step(), 8);
+    assert_eq!(unsafe { image.row_as::<u16>(1) }[..3], [1007, 1307, 1607]);
+    assert_eq!(ImageBuffer::<Luma<u16>, Vec<u16>>::try_from(&image).unwrap(), buffer);
+    assert!(image.as_luma16().is_none());
+  }
+
+  #[test]
+  fn mismatched_layouts_are_rejected() {
+    let image = image_u8(3, 3, &[0; 9]);
+    assert!(GrayImage::try_from(&image).is_err());
+    assert!(RgbaImage::try_from(&image).is_err());
+    assert!(ImageBuffer::<Luma<u16>, Vec<u16>>::try_from(&image).is_err());
+  }
+}
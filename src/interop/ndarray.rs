@@ -0,0 +1,117 @@
+use ndarray::{Array3, ArrayView2, ArrayView3, ArrayViewMut2, ArrayViewMut3, ShapeBuilder};
+use std::mem;
+use core::Size;
+use error::Result;
+use image::{Depth, Image};
+use mat::{Element, Mat};
+
+impl Image {
+  pub fn as_array_view(&self) -> Result<ArrayView3<'_, u8>> {
+    self.expect_depth(&[Depth::U8], "array views")?;
+    let (rows, cols, channels) = (self.height() as usize, self.width() as usize, self.channels() as usize);
+    let shape = (rows, cols, channels).strides((self.width_step(), channels, 1));
+    Ok(unsafe { ArrayView3::from_shape_ptr(shape, self.data_ptr() as *const u8) })
+  }
+
+  pub fn as_array_view_mut(&mut self) -> Result<ArrayViewMut3<'_, u8>> {
+    self.expect_depth(&[Depth::U8], "array views")?;
+    let (rows, cols, channels) = (self.height() as usize, self.width() as usize, self.channels() as usize);
+    let shape = (rows, cols, channels).strides((self.width_step(), channels, 1));
+    Ok(unsafe { ArrayViewMut3::from_shape_ptr(shape, self.data_ptr()) })
+  }
+
+  pub fn from_array(array: Array3<u8>) -> Result<Image> {
+    let (rows, cols, channels) = array.dim();
+    let mut image = Image::new(&Size::new(cols as i32, rows as i32), Depth::U8, channels as u32)?;
+    image.as_array_view_mut()?.assign(&array);
+    Ok(image)
+  }
+}
+
+impl<T: Element> Mat<T> {
+  fn strides(&self) -> (usize, usize) {
+    (unsafe { (*self.as_raw()).step as usize } / mem::size_of::<T>(), 1)
+  }
+
+  pub fn as_array_view(&self) -> ArrayView2<'_, T> {
+    let shape = (self.rows(), self.cols()).strides(self.strides());
+    unsafe { ArrayView2::from_shape_ptr(shape, (*self.as_raw()).data as *const T) }
+  }
+
+  pub fn as_array_view_mut(&mut self) -> ArrayViewMut2<'_, T> {
+    let shape = (self.rows(), self.cols()).strides(self.strides());
+    unsafe { ArrayViewMut2::from_shape_ptr(shape, (*self.as_raw()).data as *mut T) }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ndarray::Array3;
+  use core::Size;
+  use image::{Depth, Image};
+  use image::tests::{image_u8, samples_u8};
+  use mat::Mat;
+
+  #[test]
+  fn image_view_follows_padded_rows() {
+    // Rows of three BGR pixels are padded from 9 to 12 bytes.
+    let samples = (0..18).collect::<Vec<u8>>();
+    let image = image_u8(3, 3, &samples);
+    let view = image.as_array_view().unwrap();
+    assert_eq!(view.dim(), (2, 3, 3));
+    for y in 0..2 {
+      for x in 0..3 {
+        for c in 0..3 {
+          assert_eq!(view[[y, x, c]], samples[(y * 3 + x) * 3 + c]);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn image_view_mut_writes_through() {
+    let mut image = image_u8(3, 3, &[0; 18]);
+    {
+      let mut view = image.as_array_view_mut().unwrap();
+      view[[1, 2, 0]] = 7;
+      view[[0, 1, 2]] = 9;
+    }
+    let samples = samples_u8(&image);
+    assert_eq!(samples[(3 + 2) * 3], 7);
+    assert_eq!(samples[3 + 2], 9);
+    assert_eq!(samples.iter().filter(|&&s| s != 0).count(), 2);
+  }
+
+  #[test]
+  fn image_from_array() {
+    let array = Array3::from_shape_fn((2, 3, 3), |(y, x, c)| (y * 100 + x * 10 + c) as u8);
+    let image = Image::from_array(array.clone()).unwrap();
+    assert_eq!((image.width(), image.height(), image.channels()), (3, 2, 3));
+    assert_eq!(samples_u8(&image)[9..12], [100, 101, 102]);
+    assert_eq!(image.as_array_view().unwrap(), array);
+  }
+
+  #[test]
+  fn image_views_need_8_bit_data() {
+    let image = Image::new(&Size::new(3, 2), Depth::F32, 1).unwrap();
+    assert!(image.as_array_view().is_err());
+  }
+
+  #[test]
+  fn mat_views_read_and_write() {
+    let mut mat = Mat::from_slice(2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    assert_eq!(mat.as_array_view()[[1, 2]], 6.0);
+    assert_eq!(mat.as_array_view()[[0, 1]], mat[(0, 1)]);
+    mat.as_array_view_mut()[[1, 0]] = -4.0;
+    assert_eq!(mat[(1, 0)], -4.0);
+    assert_eq!(mat.to_vec(), vec![vec![1.0, 2.0, 3.0], vec![-4.0, 5.0, 6.0]]);
+  }
+
+  #[test]
+  fn mat_views_of_bytes() {
+    let mut mat = Mat::from_slice(3, 3, &[0u8, 1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+    assert_eq!(mat.as_array_view().column(1).to_vec(), vec![1, 4, 7]);
+    mat.as_array_view_mut().row_mut(2).fill(9);
+    assert_eq!(mat.row(2), &[9, 9, 9]);
+  }
+}
@@ -0,0 +1,150 @@
+use std::mem;
+use std::ptr;
+use libc::c_int;
+use ffi::core::*;
+use ffi::types::{CvArr, CvMat};
+use arithm::arr;
+use core::TermCriteria;
+use error::{Error, Result, guard};
+use image::{Depth, Image};
+use mat::Mat;
+
+// How k-means picks its first centers; `Labels` starts from an `n x 1`
+// assignment of every sample to a cluster.
+pub enum KMeansInit {
+  Random,
+  PlusPlus,
+  Labels(Mat<i32>),
+}
+
+const KMEANS_USE_INITIAL_LABELS: c_int = 1;
+const KMEANS_PP_CENTERS: c_int = 2;
+
+// Clusters the rows of `samples`, which is `count x dims`, and returns the
+// `count x 1` labels, the `k x dims` centers and the compactness.
+fn kmeans(samples: *const CvArr, count: usize, dims: usize, k: usize, criteria: &TermCriteria, attempts: u32, init: KMeansInit)
+    -> Result<(Mat<i32>, Mat<f32>, f64)> {
+  if k == 0 || k > count {
+    return Err(Error::BadArgument(format!("cvKMeans2 needs between 1 and {} clusters, got {}", count, k)));
+  }
+  if attempts == 0 {
+    return Err(Error::BadArgument("cvKMeans2 needs at least one attempt".to_string()));
+  }
+  let (labels, flags) = match init {
+    KMeansInit::Random => (Mat::new(count, 1)?, 0),
+    KMeansInit::PlusPlus => (Mat::new(count, 1)?, KMEANS_PP_CENTERS),
+    KMeansInit::Labels(labels) => {
+      if (labels.rows(), labels.cols()) != (count, 1) {
+        return Err(Error::BadArgument(format!("cvKMeans2 needs {}x1 initial labels, got {}x{}", count, labels.rows(), labels.cols())));
+      }
+      if let Some(&label) = (0..count).map(|i| &labels[(i, 0)]).find(|&&l| l < 0 || l as usize >= k) {
+        return Err(Error::BadArgument(format!("initial label {} is out of range for {} clusters", label, k)));
+      }
+      (labels, KMEANS_USE_INITIAL_LABELS)
+    },
+  };
+  let centers = Mat::new(k, dims)?;
+  let mut compactness = 0.0;
+  guard(|| unsafe {
+    cvKMeans2(samples, k as c_int, labels.as_arr(), criteria.to_raw(), attempts as c_int, ptr::null_mut(), flags,
+      centers.as_arr(), &mut compactness);
+  })?;
+  Ok((labels, centers, compactness))
+}
+
+impl Mat<f32> {
+  pub fn kmeans(&self, k: usize, criteria: &TermCriteria, attempts: u32, init: KMeansInit) -> Result<(Mat<i32>, Mat<f32>, f64)> {
+    kmeans(self.as_arr(), self.rows(), self.cols(), k, criteria, attempts, init)
+  }
+}
+
+impl Image {
+  // Clusters pixels by their channel values, labelling them in row-major order.
+  pub fn kmeans(&self, k: usize, criteria: &TermCriteria, attempts: u32, init: KMeansInit) -> Result<(Mat<i32>, Mat<f32>, f64)> {
+    let converted;
+    let samples = match self.depth()? {
+      Depth::F32 => self,
+      _ => {
+        converted = self.convert_to(Depth::F32, 1.0, 0.0)?;
+        &converted
+      },
+    };
+    let count = self.width() as usize * self.height() as usize;
+    let mut header = unsafe { mem::zeroed::<CvMat>() };
+    guard(|| unsafe { cvReshape(arr(samples), &mut header, 1, count as c_int) })?;
+    kmeans(&header as *const CvMat as *const CvArr, count, self.channels() as usize, k, criteria, attempts, init)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use core::TermCriteria;
+  use image::tests::image_u8;
+  use mat::{KMeansInit, Mat};
+
+  // Samples 0-2 sit near the origin and 3-5 near (10, 10).
+  fn two_clusters() -> Mat<f32> {
+    Mat::from_slice(6, 2, &[0.0, 0.0, 0.2, 0.0, 0.0, 0.4, 10.0, 10.0, 10.2, 10.0, 10.0, 10.4]).unwrap()
+  }
+
+  fn assert_partition(labels: &Mat<i32>) {
+    let labels = labels.col(0).to_vec();
+    assert!(labels[0] != labels[3], "{:?}", labels);
+    assert!(labels[..3].iter().all(|&l| l == labels[0]), "{:?}", labels);
+    assert!(labels[3..].iter().all(|&l| l == labels[3]), "{:?}", labels);
+  }
+
+  fn assert_center(centers: &Mat<f32>, label: i32, expected: &[f32]) {
+    let center = centers.row(label as usize);
+    assert_eq!(center.len(), expected.len());
+    assert!(center.iter().zip(expected).all(|(c, e)| (c - e).abs() < 1e-4), "{:?} != {:?}", center, expected);
+  }
+
+  #[test]
+  fn separates_two_clusters() {
+    for &plus_plus in &[false, true] {
+      let init = if plus_plus { KMeansInit::PlusPlus } else { KMeansInit::Random };
+      let (labels, centers, compactness) = two_clusters().kmeans(2, &TermCriteria::CountEps(100, 1e-6), 3, init).unwrap();
+      assert_partition(&labels);
+      assert_eq!((centers.rows(), centers.cols()), (2, 2));
+      assert_center(&centers, labels[(0, 0)], &[0.2 / 3.0, 0.4 / 3.0]);
+      assert_center(&centers, labels[(3, 0)], &[10.0 + 0.2 / 3.0, 10.0 + 0.4 / 3.0]);
+      // Each cluster deviates by (0.2, 0.4) in one sample per axis, which adds
+      // 2/3 of the squared offset to the sum of squared distances.
+      let per_cluster = (0.2f64.powi(2) + 0.4f64.powi(2)) * 2.0 / 3.0;
+      assert!((compactness - 2.0 * per_cluster).abs() < 1e-4, "{}", compactness);
+    }
+  }
+
+  #[test]
+  fn keeps_initial_labels_that_are_already_optimal() {
+    let initial = Mat::from_slice(6, 1, &[1, 1, 1, 0, 0, 0]).unwrap();
+    let (labels, centers, _) = two_clusters().kmeans(2, &TermCriteria::Count(10), 1, KMeansInit::Labels(initial)).unwrap();
+    assert_eq!(labels.col(0).to_vec(), vec![1, 1, 1, 0, 0, 0]);
+    assert_center(&centers, 1, &[0.2 / 3.0, 0.4 / 3.0]);
+  }
+
+  #[test]
+  fn clusters_image_pixels_in_row_major_order() {
+    let image = image_u8(3, 1, &[0, 250, 2, 252, 1, 251]);
+    let (labels, centers, _) = image.kmeans(2, &TermCriteria::CountEps(100, 1e-6), 3, KMeansInit::PlusPlus).unwrap();
+    let labels = labels.col(0).to_vec();
+    assert_eq!(labels.len(), 6);
+    assert!(labels[0] == labels[2] && labels[2] == labels[4] && labels[1] == labels[3] && labels[3] == labels[5] && labels[0] != labels[1]);
+    assert_center(&centers, labels[0], &[1.0]);
+    assert_center(&centers, labels[1], &[251.0]);
+  }
+
+  #[test]
+  fn rejects_bad_arguments() {
+    let samples = two_clusters();
+    let criteria = TermCriteria::Count(10);
+    assert!(samples.kmeans(0, &criteria, 1, KMeansInit::Random).is_err());
+    assert!(samples.kmeans(7, &criteria, 1, KMeansInit::Random).is_err());
+    assert!(samples.kmeans(2, &criteria, 0, KMeansInit::Random).is_err());
+    let out_of_range = Mat::from_slice(6, 1, &[0, 1, 2, 0, 1, 0]).unwrap();
+    assert!(samples.kmeans(2, &criteria, 1, KMeansInit::Labels(out_of_range)).is_err());
+    let wrong_shape = Mat::from_slice(3, 1, &[0, 1, 0]).unwrap();
+    assert!(samples.kmeans(2, &criteria, 1, KMeansInit::Labels(wrong_shape)).is_err());
+  }
+}
@@ -0,0 +1,141 @@
+use std::cmp;
+use ffi::core::*;
+use error::{Error, Result, guard};
+use mat::{DecompMethod, FloatElement, Mat};
+
+impl<T: FloatElement> Mat<T> {
+  // Returns `None` for a singular matrix. SVD yields the pseudo-inverse of
+  // non-square matrices.
+  pub fn invert(&self, method: DecompMethod) -> Result<Option<Mat<T>>> {
+    if method == DecompMethod::Qr {
+      return Err(Error::BadArgument("cvInvert does not support QR decomposition".to_string()));
+    }
+    if method != DecompMethod::Svd {
+      self.expect_square("cvInvert")?;
+    }
+    let dst = Mat::new(self.cols(), self.rows())?;
+    let result = guard(|| unsafe { cvInvert(self.as_arr(), dst.as_arr(), method as i32) })?;
+    Ok(if result == 0.0 { None } else { Some(dst) })
+  }
+
+  // Solves `self * x = b`; over-determined systems need SVD or QR and are
+  // solved in the least-squares sense. Returns `None` for a singular system.
+  pub fn solve(&self, b: &Mat<T>, method: DecompMethod) -> Result<Option<Mat<T>>> {
+    if b.rows() != self.rows() {
+      return Err(Error::BadArgument(format!("cvSolve needs a right-hand side with {} rows, got {}", self.rows(), b.rows())));
+    }
+    if method != DecompMethod::Svd && method != DecompMethod::Qr {
+      self.expect_square("cvSolve")?;
+    }
+    let dst = Mat::new(self.cols(), b.cols())?;
+    let solved = guard(|| unsafe { cvSolve(self.as_arr(), b.as_arr(), dst.as_arr(), method as i32) })?;
+    Ok(if solved == 0 { None } else { Some(dst) })
+  }
+
+  // Returns `(w, u, vt)` with singular values as a column, as `cv::SVDecomp` does.
+  pub fn svd(&self) -> Result<(Mat<T>, Mat<T>, Mat<T>)> {
+    let n = cmp::min(self.rows(), self.cols());
+    let w = Mat::new(n, 1)?;
+    let u = Mat::new(self.rows(), n)?;
+    let vt = Mat::new(n, self.cols())?;
+    guard(|| unsafe { cvSVD(self.as_arr(), w.as_arr(), u.as_arr(), vt.as_arr(), 4) })?; // CV_SVD_V_T
+    Ok((w, u, vt))
+  }
+
+  // Returns eigenvalues in descending order and the matching eigenvectors as rows.
+  pub fn eigen_vv(&self) -> Result<(Mat<T>, Mat<T>)> {
+    self.expect_square("cvEigenVV")?;
+    let n = self.rows();
+    if (0..n).any(|i| (0..i).any(|j| self[(i, j)] != self[(j, i)])) {
+      return Err(Error::BadArgument("cvEigenVV needs a symmetric matrix".to_string()));
+    }
+    let src = self.clone();
+    let values = Mat::new(n, 1)?;
+    let vectors = Mat::new(n, n)?;
+    guard(|| unsafe { cvEigenVV(src.as_arr(), vectors.as_arr(), values.as_arr(), 0.0, -1, -1) })?;
+    Ok((values, vectors))
+  }
+
+  pub fn det(&self) -> Result<f64> {
+    self.expect_square("cvDet")?;
+    guard(|| unsafe { cvDet(self.as_arr()) })
+  }
+
+  fn expect_square(&self, func: &str) -> Result<()> {
+    if self.rows() != self.cols() {
+      return Err(Error::BadArgument(format!("{} needs a square matrix, got {}x{}", func, self.rows(), self.cols())));
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use mat::{DecompMethod, Mat};
+
+  fn assert_close(mat: &Mat<f64>, expected: &[f64], eps: f64) {
+    let actual = mat.to_vec().concat();
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+      assert!((a - e).abs() < eps, "{:?} != {:?}", actual, expected);
+    }
+  }
+
+  #[test]
+  fn invert_2x2() {
+    let a = Mat::from_slice(2, 2, &[4.0, 7.0, 2.0, 6.0]).unwrap();
+    for &method in &[DecompMethod::Lu, DecompMethod::Svd] {
+      assert_close(&a.invert(method).unwrap().unwrap(), &[0.6, -0.7, -0.2, 0.4], 1e-9);
+    }
+  }
+
+  #[test]
+  fn invert_singular() {
+    let a = Mat::from_slice(2, 2, &[1.0, 2.0, 2.0, 4.0]).unwrap();
+    assert!(a.invert(DecompMethod::Lu).unwrap().is_none());
+  }
+
+  #[test]
+  fn det_3x3() {
+    let a = Mat::from_slice(3, 3, &[6.0, 1.0, 1.0, 4.0, -2.0, 5.0, 2.0, 8.0, 7.0]).unwrap();
+    assert!((a.det().unwrap() + 306.0).abs() < 1e-9);
+    assert!(Mat::<f64>::new(2, 3).unwrap().det().is_err());
+  }
+
+  #[test]
+  fn solve_known_x() {
+    let a = Mat::from_slice(3, 3, &[3.0, 2.0, -1.0, 2.0, -2.0, 4.0, -1.0, 0.5, -1.0]).unwrap();
+    let b = Mat::from_slice(3, 1, &[1.0, -2.0, 0.0]).unwrap();
+    assert_close(&a.solve(&b, DecompMethod::Lu).unwrap().unwrap(), &[1.0, -2.0, -2.0], 1e-9);
+    assert_close(&a.solve(&b, DecompMethod::Qr).unwrap().unwrap(), &[1.0, -2.0, -2.0], 1e-9);
+  }
+
+  #[test]
+  fn svd_reconstructs_the_matrix() {
+    let a = Mat::from_slice(3, 2, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    let (w, u, vt) = a.svd().unwrap();
+    assert_close(&w, &[9.525518091565107, 0.5143005806586431], 1e-9);
+
+    let mut scaled = u.clone();
+    for row in 0..scaled.rows() {
+      for col in 0..scaled.cols() {
+        scaled[(row, col)] *= w[(col, 0)];
+      }
+    }
+    let product = scaled.gemm(&vt, 1.0, None, 0.0, false, false).unwrap();
+    assert_close(&product, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 1e-9);
+  }
+
+  #[test]
+  fn eigen_of_symmetric_matrix() {
+    let a = Mat::from_slice(2, 2, &[2.0, 1.0, 1.0, 2.0]).unwrap();
+    let (values, vectors) = a.eigen_vv().unwrap();
+    assert_close(&values, &[3.0, 1.0], 1e-9);
+    for i in 0..2 {
+      let v = Mat::from_slice(2, 1, vectors.row(i)).unwrap();
+      let av = a.gemm(&v, 1.0, None, 0.0, false, false).unwrap();
+      assert_close(&av, &[values[(i, 0)] * v[(0, 0)], values[(i, 0)] * v[(1, 0)]], 1e-9);
+    }
+    assert!(Mat::from_slice(2, 2, &[1.0, 2.0, 3.0, 4.0]).unwrap().eigen_vv().is_err());
+  }
+}
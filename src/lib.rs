@@ -3,14 +3,37 @@
 
 #![deny(unused_imports)]
 
-#[macro_use] extern crate enum_primitive;
+#[cfg_attr(feature = "highgui", macro_use)] extern crate enum_primitive;
 extern crate num;
 
 extern crate libc;
+#[cfg(feature = "image")]
+extern crate image as image_rs;
+#[cfg(feature = "ndarray")]
+extern crate ndarray;
 
 pub mod core;
+pub mod error;
+#[cfg(feature = "highgui")]
 pub mod highgui;
 pub mod image;
+pub mod mat;
+#[cfg(feature = "objdetect")]
 pub mod objdetect;
+// Built on the C FileStorage API, which OpenCV 4 removed; build.rs warns
+// when the module is left out.
+#[cfg(not(opencv_4))]
+pub mod persistence;
+#[cfg(feature = "video")]
 pub mod video;
+mod arithm;
+mod colormap;
+mod convert;
+mod copy;
+mod dxt;
 mod ffi;
+mod interop;
+mod kmeans;
+mod lapack;
+mod matmul;
+mod stat;
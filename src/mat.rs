@@ -0,0 +1,242 @@
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+use std::slice;
+use libc::c_int;
+use ffi::core::*;
+use ffi::types::{CvArr, CvMat};
+use error::{Error, Result, guard};
+pub use kmeans::KMeansInit;
+
+pub trait Element: Copy {
+  fn mat_type() -> c_int;
+}
+
+impl Element for u8 {
+  fn mat_type() -> c_int { 0 } // CV_8UC1
+}
+
+impl Element for i32 {
+  fn mat_type() -> c_int { 4 } // CV_32SC1
+}
+
+impl Element for f32 {
+  fn mat_type() -> c_int { 5 } // CV_32FC1
+}
+
+impl Element for f64 {
+  fn mat_type() -> c_int { 6 } // CV_64FC1
+}
+
+pub trait FloatElement: Element + PartialEq {}
+
+impl FloatElement for f32 {}
+impl FloatElement for f64 {}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DecompMethod {
+  Lu = 0,
+  Svd = 1,
+  Eig = 2,
+  Cholesky = 3,
+  Qr = 4,
+}
+
+pub struct Mat<T: Element> {
+  raw: *const CvMat,
+  _marker: PhantomData<T>,
+}
+
+impl<T: Element> Mat<T> {
+  pub fn new(rows: usize, cols: usize) -> Result<Mat<T>> {
+    if rows == 0 || cols == 0 {
+      return Err(Error::BadArgument(format!("invalid matrix size {}x{}", rows, cols)));
+    }
+    match guard(|| unsafe { cvCreateMat(rows as c_int, cols as c_int, T::mat_type()) })? {
+      p if !p.is_null() => {
+        let mat = Mat { raw: p, _marker: PhantomData };
+        guard(|| unsafe { cvSetZero(p as *const CvArr) })?;
+        Ok(mat)
+      },
+      _ => Err(Error::BadArgument(format!("could not allocate a {}x{} matrix", rows, cols))),
+    }
+  }
+
+  pub fn from_slice(rows: usize, cols: usize, data: &[T]) -> Result<Mat<T>> {
+    if data.len() != rows * cols {
+      return Err(Error::BadArgument(format!("{} elements do not fill a {}x{} matrix", data.len(), rows, cols)));
+    }
+    let mut mat = Mat::new(rows, cols)?;
+    for (row, values) in data.chunks(cols).enumerate() {
+      mat.row_mut(row).copy_from_slice(values);
+    }
+    Ok(mat)
+  }
+
+  pub fn rows(&self) -> usize {
+    unsafe { (*self.raw).rows as usize }
+  }
+
+  pub fn cols(&self) -> usize {
+    unsafe { (*self.raw).cols as usize }
+  }
+
+  pub fn get(&self, row: usize, col: usize) -> Option<T> {
+    if row < self.rows() && col < self.cols() { Some(self.row(row)[col]) } else { None }
+  }
+
+  pub fn row(&self, row: usize) -> &[T] {
+    assert!(row < self.rows(), "row {} out of range for a matrix with {} rows", row, self.rows());
+    unsafe {
+      let data = (*self.raw).data.add(row * (*self.raw).step as usize);
+      slice::from_raw_parts(data as *const T, self.cols())
+    }
+  }
+
+  pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+    assert!(row < self.rows(), "row {} out of range for a matrix with {} rows", row, self.rows());
+    unsafe {
+      let data = (*self.raw).data.add(row * (*self.raw).step as usize);
+      slice::from_raw_parts_mut(data as *mut T, self.cols())
+    }
+  }
+
+  pub fn col(&self, col: usize) -> Col<'_, T> {
+    assert!(col < self.cols(), "column {} out of range for a matrix with {} columns", col, self.cols());
+    Col { mat: self, col: col }
+  }
+
+  pub fn to_vec(&self) -> Vec<Vec<T>> {
+    (0..self.rows()).map(|row| self.row(row).to_vec()).collect()
+  }
+
+  pub(crate) fn as_raw(&self) -> *const CvMat {
+    self.raw
+  }
+
+  pub(crate) fn as_arr(&self) -> *const CvArr {
+    self.raw as *const CvArr
+  }
+
+  pub(crate) unsafe fn from_raw(raw: *const CvMat) -> Result<Mat<T>> {
+    match (*raw).type_ & 0xFFF { // CV_MAT_TYPE_MASK
+      t if t == T::mat_type() => Ok(Mat { raw: raw, _marker: PhantomData }),
+      t => {
+        cvReleaseMat(&raw);
+        Err(Error::BadArgument(format!("matrix of type {} does not hold elements of type {}", t, T::mat_type())))
+      }
+    }
+  }
+}
+
+impl<T: Element> Index<(usize, usize)> for Mat<T> {
+  type Output = T;
+  fn index(&self, (row, col): (usize, usize)) -> &T {
+    &self.row(row)[col]
+  }
+}
+
+impl<T: Element> IndexMut<(usize, usize)> for Mat<T> {
+  fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+    &mut self.row_mut(row)[col]
+  }
+}
+
+impl<'a, T: Element> TryFrom<&'a [Vec<T>]> for Mat<T> {
+  type Error = Error;
+  fn try_from(rows: &'a [Vec<T>]) -> Result<Mat<T>> {
+    let cols = rows.first().map_or(0, |row| row.len());
+    if let Some(row) = rows.iter().find(|row| row.len() != cols) {
+      return Err(Error::BadArgument(format!("ragged rows of length {} and {}", cols, row.len())));
+    }
+    let mut mat = Mat::new(rows.len(), cols)?;
+    for (i, row) in rows.iter().enumerate() {
+      mat.row_mut(i).copy_from_slice(row);
+    }
+    Ok(mat)
+  }
+}
+
+impl<T: Element> TryFrom<Vec<Vec<T>>> for Mat<T> {
+  type Error = Error;
+  fn try_from(rows: Vec<Vec<T>>) -> Result<Mat<T>> {
+    Mat::try_from(&rows[..])
+  }
+}
+
+impl<'a, T: Element> From<&'a Mat<T>> for Vec<Vec<T>> {
+  fn from(mat: &'a Mat<T>) -> Vec<Vec<T>> {
+    mat.to_vec()
+  }
+}
+
+impl<T: Element> From<Mat<T>> for Vec<Vec<T>> {
+  fn from(mat: Mat<T>) -> Vec<Vec<T>> {
+    mat.to_vec()
+  }
+}
+
+impl<T: Element> Clone for Mat<T> {
+  fn clone(&self) -> Mat<T> {
+    let raw = guard(|| unsafe { cvCloneMat(self.raw) }).unwrap_or_else(|e| panic!("cannot clone matrix: {}", e));
+    Mat { raw: raw, _marker: PhantomData }
+  }
+}
+
+unsafe impl<T: Element + Send> Send for Mat<T> {}
+unsafe impl<T: Element + Sync> Sync for Mat<T> {}
+
+impl<T: Element> Drop for Mat<T> {
+  fn drop(&mut self) {
+    unsafe { cvReleaseMat(&self.raw); }
+  }
+}
+
+pub struct Col<'a, T: Element + 'a> {
+  mat: &'a Mat<T>,
+  col: usize,
+}
+
+impl<'a, T: Element> Col<'a, T> {
+  pub fn len(&self) -> usize {
+    self.mat.rows()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  pub fn get(&self, row: usize) -> Option<T> {
+    self.mat.get(row, self.col)
+  }
+
+  pub fn iter(&self) -> ColIter<'a, T> {
+    ColIter { mat: self.mat, col: self.col, row: 0 }
+  }
+
+  pub fn to_vec(&self) -> Vec<T> {
+    self.iter().collect()
+  }
+}
+
+impl<'a, T: Element> Index<usize> for Col<'a, T> {
+  type Output = T;
+  fn index(&self, row: usize) -> &T {
+    &self.mat[(row, self.col)]
+  }
+}
+
+pub struct ColIter<'a, T: Element + 'a> {
+  mat: &'a Mat<T>,
+  col: usize,
+  row: usize,
+}
+
+impl<'a, T: Element> Iterator for ColIter<'a, T> {
+  type Item = T;
+  fn next(&mut self) -> Option<T> {
+    let value = self.mat.get(self.row, self.col);
+    if value.is_some() { self.row += 1; }
+    value
+  }
+}
@@ -0,0 +1,112 @@
+use libc::{c_int, c_void};
+use std::mem;
+use std::ptr;
+use ffi::core::*;
+use ffi::types::{CvArr, CvMat};
+use core::Point2d;
+use error::{Error, Result, guard};
+use mat::{FloatElement, Mat};
+
+impl<T: FloatElement> Mat<T> {
+  // Computes `alpha * op(self) * op(b) + beta * c`, where op optionally transposes.
+  pub fn gemm(&self, b: &Mat<T>, alpha: f64, c: Option<&Mat<T>>, beta: f64, transpose_a: bool, transpose_b: bool) -> Result<Mat<T>> {
+    let (rows, inner) = if transpose_a { (self.cols(), self.rows()) } else { (self.rows(), self.cols()) };
+    let (b_inner, cols) = if transpose_b { (b.cols(), b.rows()) } else { (b.rows(), b.cols()) };
+    if inner != b_inner {
+      return Err(Error::BadArgument(format!("cannot multiply {}x{} by {}x{}", rows, inner, b_inner, cols)));
+    }
+    if let Some(c) = c {
+      if c.rows() != rows || c.cols() != cols {
+        return Err(Error::BadArgument(format!("cvGEMM needs a {}x{} addend, got {}x{}", rows, cols, c.rows(), c.cols())));
+      }
+    }
+
+    let flags = (if transpose_a { 1 } else { 0 }) | (if transpose_b { 2 } else { 0 }); // CV_GEMM_A_T, CV_GEMM_B_T
+    let dst = Mat::new(rows, cols)?;
+    guard(|| unsafe { cvGEMM(self.as_arr(), b.as_arr(), alpha, c.map_or(ptr::null(), |c| c.as_arr()), beta, dst.as_arr(), flags) })?;
+    Ok(dst)
+  }
+
+  pub fn transpose(&self) -> Result<Mat<T>> {
+    let dst = Mat::new(self.cols(), self.rows())?;
+    guard(|| unsafe { cvTranspose(self.as_arr(), dst.as_arr()) })?;
+    Ok(dst)
+  }
+
+  pub fn cross(&self, other: &Mat<T>) -> Result<Mat<T>> {
+    let is_vector3 = |m: &Mat<T>| (m.rows(), m.cols()) == (3, 1) || (m.rows(), m.cols()) == (1, 3);
+    if !is_vector3(self) || (self.rows(), self.cols()) != (other.rows(), other.cols()) {
+      return Err(Error::BadArgument(format!("cvCrossProduct needs two 3-element vectors of the same shape, got {}x{} and {}x{}",
+        self.rows(), self.cols(), other.rows(), other.cols())));
+    }
+    let dst = Mat::new(self.rows(), self.cols())?;
+    guard(|| unsafe { cvCrossProduct(self.as_arr(), other.as_arr(), dst.as_arr()) })?;
+    Ok(dst)
+  }
+
+  pub fn perspective_transform(&self, points: &[Point2d]) -> Result<Vec<Point2d>> {
+    if self.rows() != 3 || self.cols() != 3 {
+      return Err(Error::BadArgument(format!("perspective transforms are 3x3, got {}x{}", self.rows(), self.cols())));
+    }
+    if points.is_empty() {
+      return Ok(Vec::new());
+    }
+
+    let mut src = points.iter().map(|p| [p.x, p.y]).collect::<Vec<[f64;2]>>();
+    let mut dst = vec![[0.0;2]; points.len()];
+    let (mut src_header, mut dst_header) = unsafe { (mem::zeroed::<CvMat>(), mem::zeroed::<CvMat>()) };
+    guard(|| unsafe {
+      let n = points.len() as c_int;
+      cvInitMatHeader(&mut src_header, 1, n, 14, src.as_mut_ptr() as *mut c_void, 0x7fffffff); // CV_64FC2, CV_AUTOSTEP
+      cvInitMatHeader(&mut dst_header, 1, n, 14, dst.as_mut_ptr() as *mut c_void, 0x7fffffff);
+      cvPerspectiveTransform(&src_header as *const CvMat as *const CvArr, &dst_header as *const CvMat as *const CvArr, self.as_raw());
+    })?;
+    Ok(dst.iter().map(|p| Point2d::new(p[0], p[1])).collect())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use mat::Mat;
+
+  fn assert_close(mat: &Mat<f64>, expected: &[f64]) {
+    let actual = mat.to_vec().concat();
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected) {
+      assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+  }
+
+  #[test]
+  fn gemm() {
+    let a = Mat::from_slice(2, 2, &[1.0, 2.0, 3.0, 4.0]).unwrap();
+    let b = Mat::from_slice(2, 2, &[5.0, 6.0, 7.0, 8.0]).unwrap();
+    let identity = Mat::from_slice(2, 2, &[1.0, 0.0, 0.0, 1.0]).unwrap();
+    assert_close(&a.gemm(&b, 1.0, None, 0.0, false, false).unwrap(), &[19.0, 22.0, 43.0, 50.0]);
+    assert_close(&a.gemm(&b, 1.0, None, 0.0, true, false).unwrap(), &[26.0, 30.0, 38.0, 44.0]);
+    assert_close(&a.gemm(&b, 2.0, Some(&identity), 1.0, false, false).unwrap(), &[39.0, 44.0, 86.0, 101.0]);
+  }
+
+  #[test]
+  fn gemm_rejects_mismatched_shapes() {
+    let a = Mat::<f64>::new(2, 3).unwrap();
+    assert!(a.gemm(&a, 1.0, None, 0.0, false, false).is_err());
+    assert!(a.gemm(&a, 1.0, None, 0.0, false, true).is_ok());
+  }
+
+  #[test]
+  fn transpose() {
+    let a = Mat::from_slice(2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    assert_close(&a.transpose().unwrap(), &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+  }
+
+  #[test]
+  fn cross() {
+    let x = Mat::from_slice(3, 1, &[1.0, 0.0, 0.0]).unwrap();
+    let y = Mat::from_slice(3, 1, &[0.0, 1.0, 0.0]).unwrap();
+    assert_close(&x.cross(&y).unwrap(), &[0.0, 0.0, 1.0]);
+    let a = Mat::from_slice(1, 3, &[1.0, 2.0, 3.0]).unwrap();
+    let b = Mat::from_slice(1, 3, &[4.0, 5.0, 6.0]).unwrap();
+    assert_close(&a.cross(&b).unwrap(), &[-3.0, 6.0, -3.0]);
+  }
+}
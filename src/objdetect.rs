@@ -1,48 +1,142 @@
 use ffi::types::{CvArr, CvHaarClassifierCascade, CvSize};
+use libc::c_char;
+#[cfg(not(opencv_4))]
 use std::ptr;
-use core::{Size, Seq, as_c_str};
+use std::ops::Deref;
+use std::sync::Mutex;
+use core::{MemStorage, Rect, Size, Seq, as_c_str};
+use error::{Error, Result, guard};
 use image::{Image};
 use ffi::objdetect::*;
+#[cfg(not(opencv_4))]
 use ffi::core::*;
 
-use std::path::Path;
-
+use std::path::{Path, PathBuf};
 
+// Owns a Haar cascade. `cvHaarDetectObjects` writes scale data into the
+// cascade, so a classifier can move between threads but not be shared;
+// use `CascadePool` to detect from several threads at once.
 pub struct CascadeClassifier {
   raw: *mut CvHaarClassifierCascade
 }
 
+unsafe impl Send for CascadeClassifier {}
+
 impl CascadeClassifier {
-  pub fn load(path: &Path) -> Result<CascadeClassifier, String> {
+  pub fn load(path: &Path) -> Result<CascadeClassifier> {
     let path_c_str = as_c_str(path);
-    unsafe {
-      match cvLoad(path_c_str.as_ptr(), ptr::null_mut(), ptr::null(), ptr::null()) {
-        c if !c.is_null() => Ok(CascadeClassifier { raw: c as *mut CvHaarClassifierCascade }),
-        _ => Err(String::from_utf8_lossy(path_c_str.to_bytes()).into_owned()),
-      }
+    match guard(|| unsafe { load_cascade(path_c_str.as_ptr()) })? {
+      c if !c.is_null() => Ok(CascadeClassifier { raw: c }),
+      _ => Err(Error::from_path(path)),
     }
   }
 
   pub fn detect_multi_scale(&self, image: &Image,
     scale_factor: f64, min_neighbors: i32, flags: i32,
-    min_size: Size, max_size: Size) -> Result<Seq, String> {
+    min_size: Size, max_size: Size) -> Result<Vec<Rect>> {
+
+    let storage = MemStorage::new()?;
+    let objects = self.detect_multi_scale_in(image, &storage, scale_factor, min_neighbors, flags, min_size, max_size)?;
+    Ok(objects.iter().collect())
+  }
+
+  pub fn detect_multi_scale_in<'s>(&self, image: &Image, storage: &'s MemStorage,
+    scale_factor: f64, min_neighbors: i32, flags: i32,
+    min_size: Size, max_size: Size) -> Result<Seq<'s, Rect>> {
+
+    if !(scale_factor > 1.0) {
+      return Err(Error::BadArgument(format!("scale_factor must be greater than 1, got {}", scale_factor)));
+    }
+    if min_neighbors < 0 {
+      return Err(Error::BadArgument(format!("min_neighbors must not be negative, got {}", min_neighbors)));
+    }
 
-    unsafe {
-      match cvHaarDetectObjects(
-        image.raw as *const CvArr,
+    let seq = guard(|| unsafe {
+      cvHaarDetectObjects(
+        image.as_raw() as *const CvArr,
         self.raw,
-        cvCreateMemStorage(0),
+        storage.as_raw(),
         scale_factor,
         min_neighbors as i32,
         flags as i32,
         CvSize { width: min_size.width as i32, height: min_size.height as i32 },
         CvSize { width: max_size.width as i32, height: max_size.height as i32 }
-      ) {
-        r if !r.is_null() => Ok(Seq { raw: r, curr: 0 }),
-        _ => Err("Something went wrong!".to_string())
-      }
+      )
+    })?;
+    match unsafe { Seq::from_raw(seq) } {
+      Some(seq) => Ok(seq),
+      None => Err(Error::BadArgument("cvHaarDetectObjects returned no sequence".to_string()))
     }
 
   }
+}
+
+#[cfg(not(opencv_4))]
+unsafe fn load_cascade(path: *const c_char) -> *mut CvHaarClassifierCascade {
+  cvLoad(path, ptr::null_mut(), ptr::null(), ptr::null()) as *mut CvHaarClassifierCascade
+}
+
+#[cfg(opencv_4)]
+unsafe fn load_cascade(path: *const c_char) -> *mut CvHaarClassifierCascade {
+  cvLoadHaarClassifierCascade(path)
+}
+
+impl Drop for CascadeClassifier {
+  fn drop(&mut self) {
+    unsafe { cvReleaseHaarClassifierCascade(&mut self.raw); }
+  }
+}
+
+// A `Sync` set of classifiers loaded from the same file. Each detection
+// borrows an idle classifier, loading another one when every classifier is
+// busy, so concurrent callers never share cascade state.
+pub struct CascadePool {
+  path: PathBuf,
+  idle: Mutex<Vec<CascadeClassifier>>,
+}
+
+impl CascadePool {
+  pub fn load(path: &Path) -> Result<CascadePool> {
+    let classifier = CascadeClassifier::load(path)?;
+    Ok(CascadePool { path: path.to_path_buf(), idle: Mutex::new(vec![classifier]) })
+  }
+
+  pub fn with<R, F: FnOnce(&CascadeClassifier) -> R>(&self, f: F) -> Result<R> {
+    let idle = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+    let classifier = match idle {
+      Some(classifier) => classifier,
+      None => CascadeClassifier::load(&self.path)?,
+    };
+    let lease = Lease { pool: self, classifier: Some(classifier) };
+    Ok(f(&lease))
+  }
+
+  pub fn detect_multi_scale(&self, image: &Image,
+    scale_factor: f64, min_neighbors: i32, flags: i32,
+    min_size: Size, max_size: Size) -> Result<Vec<Rect>> {
+
+    self.with(|classifier| classifier.detect_multi_scale(image, scale_factor, min_neighbors, flags, min_size, max_size))?
+  }
+}
 
+// Hands a classifier back to the pool when dropped, even if the closure
+// given to `CascadePool::with` panics.
+struct Lease<'a> {
+  pool: &'a CascadePool,
+  classifier: Option<CascadeClassifier>,
+}
+
+impl<'a> Deref for Lease<'a> {
+  type Target = CascadeClassifier;
+  fn deref(&self) -> &CascadeClassifier {
+    self.classifier.as_ref().expect("a lease holds its classifier until dropped")
+  }
+}
+
+impl<'a> Drop for Lease<'a> {
+  fn drop(&mut self) {
+    if let Some(classifier) = self.classifier.take() {
+      self.pool.idle.lock().unwrap_or_else(|e| e.into_inner()).push(classifier);
+    }
+  }
 }
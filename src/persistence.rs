@@ -0,0 +1,592 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::CString;
+use std::path::Path;
+use std::{ptr, slice};
+use libc::{c_char, c_int, c_void};
+use ffi::core::*;
+use ffi::types::{CvAttrList, CvFileMapNode, CvFileNode, CvFileStorage, CvMat, CvSeq};
+use core::{Point, Point2d, Point2f, Point3f, Rect, RotatedRect, Scalar, Size, Size2f, as_c_str};
+use error::{Error, Result, guard};
+use mat::{Element, Mat};
+
+const CV_NODE_INT: c_int = 1;
+const CV_NODE_REAL: c_int = 2;
+const CV_NODE_STR: c_int = 3;
+const CV_NODE_SEQ: c_int = 5;
+const CV_NODE_MAP: c_int = 6;
+const CV_NODE_TYPE_MASK: c_int = 7;
+const CV_NODE_FLOW: c_int = 8;
+const CV_NODE_USER: c_int = 16;
+const CV_MAGIC_MASK: c_int = 0xFFFF0000u32 as c_int;
+const CV_MAT_MAGIC_VAL: c_int = 0x42420000;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mode {
+  Read = 0,
+  Write = 1,
+  Append = 2,
+}
+
+pub trait Persist: Sized {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()>;
+  fn read(node: &FileNode) -> Result<Self>;
+}
+
+fn no_attributes() -> CvAttrList {
+  CvAttrList { attr: ptr::null(), next: ptr::null_mut() }
+}
+
+fn to_c_string(s: &str) -> Result<CString> {
+  CString::new(s).map_err(|_| Error::BadArgument(format!("{:?} contains a NUL byte", s)))
+}
+
+fn name_ptr(name: &Option<CString>) -> *const c_char {
+  name.as_ref().map_or(ptr::null(), |n| n.as_ptr())
+}
+
+pub struct FileStorage {
+  raw: *mut CvFileStorage,
+}
+
+impl FileStorage {
+  pub fn open(path: &Path, mode: Mode) -> Result<FileStorage> {
+    let path_c_str = as_c_str(path);
+    match guard(|| unsafe { cvOpenFileStorage(path_c_str.as_ptr(), ptr::null_mut(), mode as c_int, ptr::null()) })? {
+      p if !p.is_null() => Ok(FileStorage { raw: p }),
+      _ => Err(Error::from_path(path)),
+    }
+  }
+
+  pub fn write<T: Persist>(&mut self, name: &str, value: &T) -> Result<()> {
+    value.write(self, Some(name))
+  }
+
+  pub fn read<T: Persist>(&self, name: &str) -> Result<T> {
+    match self.node(name) {
+      Some(node) => node.read(),
+      None => Err(Error::BadArgument(format!("no node named {:?}", name))),
+    }
+  }
+
+  pub fn node(&self, name: &str) -> Option<FileNode<'_>> {
+    let name = CString::new(name).ok()?;
+    unsafe { FileNode::from_raw(self, cvGetFileNodeByName(self.raw, ptr::null(), name.as_ptr())) }
+  }
+
+  pub fn root(&self) -> Option<FileNode<'_>> {
+    unsafe { FileNode::from_raw(self, cvGetRootFileNode(self.raw, 0)) }
+  }
+
+  pub fn write_int(&mut self, name: Option<&str>, value: i32) -> Result<()> {
+    let name = match name { Some(n) => Some(to_c_string(n)?), None => None };
+    guard(|| unsafe { cvWriteInt(self.raw, name_ptr(&name), value as c_int) })
+  }
+
+  pub fn write_real(&mut self, name: Option<&str>, value: f64) -> Result<()> {
+    let name = match name { Some(n) => Some(to_c_string(n)?), None => None };
+    guard(|| unsafe { cvWriteReal(self.raw, name_ptr(&name), value) })
+  }
+
+  pub fn write_string(&mut self, name: Option<&str>, value: &str) -> Result<()> {
+    let name = match name { Some(n) => Some(to_c_string(n)?), None => None };
+    let value = to_c_string(value)?;
+    guard(|| unsafe { cvWriteString(self.raw, name_ptr(&name), value.as_ptr(), 1) })
+  }
+
+  pub fn start_seq(&mut self, name: Option<&str>, flow: bool) -> Result<()> {
+    self.start_struct(name, CV_NODE_SEQ | if flow { CV_NODE_FLOW } else { 0 })
+  }
+
+  pub fn start_map(&mut self, name: Option<&str>, flow: bool) -> Result<()> {
+    self.start_struct(name, CV_NODE_MAP | if flow { CV_NODE_FLOW } else { 0 })
+  }
+
+  fn start_struct(&mut self, name: Option<&str>, flags: c_int) -> Result<()> {
+    let name = match name { Some(n) => Some(to_c_string(n)?), None => None };
+    guard(|| unsafe { cvStartWriteStruct(self.raw, name_ptr(&name), flags, ptr::null(), no_attributes()) })
+  }
+
+  pub fn end_struct(&mut self) -> Result<()> {
+    guard(|| unsafe { cvEndWriteStruct(self.raw) })
+  }
+
+  fn write_flow_seq(&mut self, name: Option<&str>, values: &[f64], as_int: bool) -> Result<()> {
+    self.start_seq(name, true)?;
+    for &value in values {
+      if as_int { self.write_int(None, value as i32)?; } else { self.write_real(None, value)?; }
+    }
+    self.end_struct()
+  }
+}
+
+impl Drop for FileStorage {
+  fn drop(&mut self) {
+    unsafe { cvReleaseFileStorage(&mut self.raw); }
+  }
+}
+
+pub struct FileNode<'fs> {
+  fs: &'fs FileStorage,
+  raw: *const CvFileNode,
+}
+
+impl<'fs> FileNode<'fs> {
+  unsafe fn from_raw(fs: &'fs FileStorage, raw: *const CvFileNode) -> Option<FileNode<'fs>> {
+    if raw.is_null() { None } else { Some(FileNode { fs: fs, raw: raw }) }
+  }
+
+  fn tag(&self) -> c_int {
+    unsafe { (*self.raw).tag & CV_NODE_TYPE_MASK }
+  }
+
+  pub fn is_int(&self) -> bool { self.tag() == CV_NODE_INT }
+  pub fn is_real(&self) -> bool { self.tag() == CV_NODE_REAL }
+  pub fn is_string(&self) -> bool { self.tag() == CV_NODE_STR }
+  pub fn is_seq(&self) -> bool { self.tag() == CV_NODE_SEQ }
+  pub fn is_map(&self) -> bool { self.tag() == CV_NODE_MAP }
+
+  pub fn as_i32(&self) -> Result<i32> {
+    match self.tag() {
+      CV_NODE_INT => Ok(unsafe { (*self.raw).data.i as i32 }),
+      _ => Err(Error::BadArgument("node is not an integer".to_string())),
+    }
+  }
+
+  pub fn as_f64(&self) -> Result<f64> {
+    match self.tag() {
+      CV_NODE_INT => Ok(unsafe { (*self.raw).data.i as f64 }),
+      CV_NODE_REAL => Ok(unsafe { (*self.raw).data.f }),
+      _ => Err(Error::BadArgument("node is not a number".to_string())),
+    }
+  }
+
+  pub fn as_string(&self) -> Result<String> {
+    match self.tag() {
+      CV_NODE_STR => unsafe {
+        let s = (*self.raw).data.str_;
+        let bytes = slice::from_raw_parts(s.ptr as *const u8, s.len as usize);
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+      },
+      _ => Err(Error::BadArgument("node is not a string".to_string())),
+    }
+  }
+
+  pub fn get(&self, name: &str) -> Option<FileNode<'fs>> {
+    if !self.is_map() {
+      return None;
+    }
+    let name = CString::new(name).ok()?;
+    unsafe { FileNode::from_raw(self.fs, cvGetFileNodeByName(self.fs.raw, self.raw, name.as_ptr())) }
+  }
+
+  pub fn len(&self) -> usize {
+    match self.tag() {
+      CV_NODE_SEQ => unsafe { (*(*self.raw).data.seq).total as usize },
+      CV_NODE_MAP => self.entries().len(),
+      _ => 0,
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  pub fn elements(&self) -> Vec<FileNode<'fs>> {
+    if !self.is_seq() {
+      return Vec::new();
+    }
+    unsafe {
+      let seq = (*self.raw).data.seq;
+      (0..(*seq).total)
+        .filter_map(|i| FileNode::from_raw(self.fs, cvGetSeqElem(seq, i) as *const CvFileNode))
+        .collect()
+    }
+  }
+
+  pub fn entries(&self) -> Vec<(String, FileNode<'fs>)> {
+    if !self.is_map() {
+      return Vec::new();
+    }
+    unsafe {
+      let map: *const CvSeq = (*self.raw).data.map;
+      (0..(*map).total)
+        .map(|i| cvGetSeqElem(map, i) as *const CvFileMapNode)
+        .filter(|&node| !node.is_null() && (*node).value.tag >= 0) // CV_IS_SET_ELEM
+        .map(|node| {
+          let key = (*(*node).key).str_;
+          let key = String::from_utf8_lossy(slice::from_raw_parts(key.ptr as *const u8, key.len as usize)).into_owned();
+          (key, FileNode { fs: self.fs, raw: &(*node).value })
+        })
+        .collect()
+    }
+  }
+
+  pub fn read<T: Persist>(&self) -> Result<T> {
+    T::read(self)
+  }
+
+  fn read_numbers(&self, count: usize) -> Result<Vec<f64>> {
+    let elements = self.elements();
+    if elements.len() != count {
+      return Err(Error::BadArgument(format!("expected a sequence of {} numbers, got {} elements", count, elements.len())));
+    }
+    elements.iter().map(|e| e.as_f64()).collect()
+  }
+}
+
+impl Persist for i32 {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_int(name, *self)
+  }
+  fn read(node: &FileNode) -> Result<i32> {
+    node.as_i32()
+  }
+}
+
+impl Persist for f32 {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_real(name, *self as f64)
+  }
+  fn read(node: &FileNode) -> Result<f32> {
+    node.as_f64().map(|v| v as f32)
+  }
+}
+
+impl Persist for f64 {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_real(name, *self)
+  }
+  fn read(node: &FileNode) -> Result<f64> {
+    node.as_f64()
+  }
+}
+
+impl Persist for bool {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_int(name, if *self { 1 } else { 0 })
+  }
+  fn read(node: &FileNode) -> Result<bool> {
+    node.as_i32().map(|v| v != 0)
+  }
+}
+
+impl Persist for String {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_string(name, self)
+  }
+  fn read(node: &FileNode) -> Result<String> {
+    node.as_string()
+  }
+}
+
+impl<T: Persist> Persist for Vec<T> {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.start_seq(name, false)?;
+    for value in self {
+      value.write(fs, None)?;
+    }
+    fs.end_struct()
+  }
+  fn read(node: &FileNode) -> Result<Vec<T>> {
+    if !node.is_seq() {
+      return Err(Error::BadArgument("node is not a sequence".to_string()));
+    }
+    node.elements().iter().map(|e| e.read()).collect()
+  }
+}
+
+impl<T: Persist> Persist for BTreeMap<String, T> {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.start_map(name, false)?;
+    for (key, value) in self {
+      value.write(fs, Some(key))?;
+    }
+    fs.end_struct()
+  }
+  fn read(node: &FileNode) -> Result<BTreeMap<String, T>> {
+    if !node.is_map() {
+      return Err(Error::BadArgument("node is not a map".to_string()));
+    }
+    node.entries().into_iter().map(|(key, value)| Ok((key, value.read()?))).collect()
+  }
+}
+
+impl<T: Persist> Persist for HashMap<String, T> {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.start_map(name, false)?;
+    for (key, value) in self {
+      value.write(fs, Some(key))?;
+    }
+    fs.end_struct()
+  }
+  fn read(node: &FileNode) -> Result<HashMap<String, T>> {
+    if !node.is_map() {
+      return Err(Error::BadArgument("node is not a map".to_string()));
+    }
+    node.entries().into_iter().map(|(key, value)| Ok((key, value.read()?))).collect()
+  }
+}
+
+// cvRead and cvLoad return whatever object was stored. Every OpenCV object
+// starts with an int holding its magic number or, for IplImage, its size.
+unsafe fn into_mat<T: Element>(object: *mut c_void) -> Result<Mat<T>> {
+  if *(object as *const c_int) & CV_MAGIC_MASK == CV_MAT_MAGIC_VAL {
+    return Mat::from_raw(object as *const CvMat);
+  }
+  let mut object = object;
+  guard(|| cvRelease(&mut object))?;
+  Err(Error::BadArgument("stored object is not a matrix".to_string()))
+}
+
+impl<T: Element> Persist for Mat<T> {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    let name = match name { Some(n) => Some(to_c_string(n)?), None => None };
+    guard(|| unsafe { cvWrite(fs.raw, name_ptr(&name), self.as_raw() as *const c_void, no_attributes()) })
+  }
+  fn read(node: &FileNode) -> Result<Mat<T>> {
+    if unsafe { (*node.raw).tag } & CV_NODE_USER == 0 {
+      return Err(Error::BadArgument("node is not a matrix".to_string()));
+    }
+    match guard(|| unsafe { cvRead(node.fs.raw, node.raw as *mut CvFileNode, ptr::null_mut()) })? {
+      p if !p.is_null() => unsafe { into_mat(p) },
+      _ => Err(Error::BadArgument("node is not a matrix".to_string())),
+    }
+  }
+}
+
+impl<T: Element> Mat<T> {
+  pub fn save(&self, path: &Path) -> Result<()> {
+    let path_c_str = as_c_str(path);
+    guard(|| unsafe { cvSave(path_c_str.as_ptr(), self.as_raw() as *const c_void, ptr::null(), ptr::null(), no_attributes()) })
+  }
+
+  pub fn load(path: &Path) -> Result<Mat<T>> {
+    let path_c_str = as_c_str(path);
+    match guard(|| unsafe { cvLoad(path_c_str.as_ptr(), ptr::null_mut(), ptr::null(), ptr::null()) })? {
+      p if !p.is_null() => unsafe { into_mat(p) },
+      _ => Err(Error::from_path(path)),
+    }
+  }
+}
+
+impl Persist for Point {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_flow_seq(name, &[self.x as f64, self.y as f64], true)
+  }
+  fn read(node: &FileNode) -> Result<Point> {
+    let v = node.read_numbers(2)?;
+    Ok(Point::new(v[0] as i32, v[1] as i32))
+  }
+}
+
+impl Persist for Point2f {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_flow_seq(name, &[self.x as f64, self.y as f64], false)
+  }
+  fn read(node: &FileNode) -> Result<Point2f> {
+    let v = node.read_numbers(2)?;
+    Ok(Point2f::new(v[0] as f32, v[1] as f32))
+  }
+}
+
+impl Persist for Point2d {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_flow_seq(name, &[self.x, self.y], false)
+  }
+  fn read(node: &FileNode) -> Result<Point2d> {
+    let v = node.read_numbers(2)?;
+    Ok(Point2d::new(v[0], v[1]))
+  }
+}
+
+impl Persist for Point3f {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_flow_seq(name, &[self.x as f64, self.y as f64, self.z as f64], false)
+  }
+  fn read(node: &FileNode) -> Result<Point3f> {
+    let v = node.read_numbers(3)?;
+    Ok(Point3f::new(v[0] as f32, v[1] as f32, v[2] as f32))
+  }
+}
+
+impl Persist for Size {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_flow_seq(name, &[self.width as f64, self.height as f64], true)
+  }
+  fn read(node: &FileNode) -> Result<Size> {
+    let v = node.read_numbers(2)?;
+    Ok(Size::new(v[0] as i32, v[1] as i32))
+  }
+}
+
+impl Persist for Size2f {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_flow_seq(name, &[self.width as f64, self.height as f64], false)
+  }
+  fn read(node: &FileNode) -> Result<Size2f> {
+    let v = node.read_numbers(2)?;
+    Ok(Size2f::new(v[0] as f32, v[1] as f32))
+  }
+}
+
+impl Persist for Rect {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_flow_seq(name, &[self.x as f64, self.y as f64, self.width as f64, self.height as f64], true)
+  }
+  fn read(node: &FileNode) -> Result<Rect> {
+    let v = node.read_numbers(4)?;
+    Ok(Rect::new(v[0] as i32, v[1] as i32, v[2] as i32, v[3] as i32))
+  }
+}
+
+impl Persist for RotatedRect {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    let values = [self.center.x, self.center.y, self.size.width, self.size.height, self.angle];
+    fs.write_flow_seq(name, &values.iter().map(|&v| v as f64).collect::<Vec<_>>(), false)
+  }
+  fn read(node: &FileNode) -> Result<RotatedRect> {
+    let v = node.read_numbers(5)?;
+    Ok(RotatedRect::new(Point2f::new(v[0] as f32, v[1] as f32), Size2f::new(v[2] as f32, v[3] as f32), v[4] as f32))
+  }
+}
+
+impl Persist for Scalar {
+  fn write(&self, fs: &mut FileStorage, name: Option<&str>) -> Result<()> {
+    fs.write_flow_seq(name, self, false)
+  }
+  fn read(node: &FileNode) -> Result<Scalar> {
+    let v = node.read_numbers(4)?;
+    Ok([v[0], v[1], v[2], v[3]])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::{BTreeMap, HashMap};
+  use std::env;
+  use std::fs;
+  use std::path::PathBuf;
+  use std::process;
+  use core::{Point, Point2d, Point2f, Point3f, Rect, RotatedRect, Size, Size2f};
+  use mat::Mat;
+  use super::{FileStorage, Mode};
+
+  const FORMATS: [&str;2] = ["xml", "yml"];
+
+  fn temp_path(name: &str, ext: &str) -> PathBuf {
+    env::temp_dir().join(format!("opencv-rs-{}-{}.{}", process::id(), name, ext))
+  }
+
+  // Writes through `write`, closes the file and reopens it for `read`.
+  fn round_trip<W: FnOnce(&mut FileStorage), R: FnOnce(&FileStorage)>(name: &str, ext: &str, write: W, read: R) {
+    let path = temp_path(name, ext);
+    {
+      let mut fs = FileStorage::open(&path, Mode::Write).unwrap();
+      write(&mut fs);
+    }
+    let fs = FileStorage::open(&path, Mode::Read).unwrap();
+    read(&fs);
+    fs::remove_file(&path).unwrap();
+  }
+
+  #[test]
+  fn scalars_and_strings() {
+    for ext in &FORMATS {
+      round_trip("scalars", ext, |fs| {
+        fs.write("int", &-42i32).unwrap();
+        fs.write("float", &1.5f32).unwrap();
+        fs.write("double", &0.1f64).unwrap();
+        fs.write("yes", &true).unwrap();
+        fs.write("no", &false).unwrap();
+        fs.write("text", &"a b: <c>".to_string()).unwrap();
+      }, |fs| {
+        assert_eq!(fs.read::<i32>("int").unwrap(), -42);
+        assert_eq!(fs.read::<f32>("float").unwrap(), 1.5);
+        assert_eq!(fs.read::<f64>("double").unwrap(), 0.1);
+        assert!(fs.read::<bool>("yes").unwrap());
+        assert!(!fs.read::<bool>("no").unwrap());
+        assert_eq!(fs.read::<String>("text").unwrap(), "a b: <c>");
+        assert!(fs.read::<i32>("text").is_err());
+        assert!(fs.read::<i32>("missing").is_err());
+      });
+    }
+  }
+
+  #[test]
+  fn collections() {
+    let list = vec![3, 1, 2];
+    let tree = vec![("a".to_string(), 1.0), ("b".to_string(), -2.5)].into_iter().collect::<BTreeMap<_, _>>();
+    let hash = vec![("x".to_string(), vec![1, 2]), ("y".to_string(), vec![])].into_iter().collect::<HashMap<_, _>>();
+    for ext in &FORMATS {
+      round_trip("collections", ext, |fs| {
+        fs.write("list", &list).unwrap();
+        fs.write("tree", &tree).unwrap();
+        fs.write("hash", &hash).unwrap();
+      }, |fs| {
+        assert_eq!(fs.read::<Vec<i32>>("list").unwrap(), list);
+        assert_eq!(fs.read::<BTreeMap<String, f64>>("tree").unwrap(), tree);
+        assert_eq!(fs.read::<HashMap<String, Vec<i32>>>("hash").unwrap(), hash);
+        assert!(fs.read::<Vec<i32>>("tree").is_err());
+      });
+    }
+  }
+
+  #[test]
+  fn matrices() {
+    let doubles = Mat::from_slice(2, 3, &[1.0, -2.0, 3.5, 0.0, 1e-9, 6.0]).unwrap();
+    let ints = Mat::from_slice(1, 2, &[7i32, -7]).unwrap();
+    for ext in &FORMATS {
+      round_trip("matrices", ext, |fs| {
+        fs.write("doubles", &doubles).unwrap();
+        fs.write("ints", &ints).unwrap();
+        fs.write("scalar", &1i32).unwrap();
+      }, |fs| {
+        assert_eq!(fs.read::<Mat<f64>>("doubles").unwrap().to_vec(), doubles.to_vec());
+        assert_eq!(fs.read::<Mat<i32>>("ints").unwrap().to_vec(), ints.to_vec());
+        assert!(fs.read::<Mat<f32>>("doubles").is_err());
+        assert!(fs.read::<Mat<f64>>("scalar").is_err());
+      });
+    }
+  }
+
+  #[test]
+  fn matrix_files() {
+    let mat = Mat::from_slice(2, 2, &[1.0f32, 2.0, 3.0, 4.0]).unwrap();
+    for ext in &FORMATS {
+      let path = temp_path("matrix-file", ext);
+      mat.save(&path).unwrap();
+      assert_eq!(Mat::<f32>::load(&path).unwrap().to_vec(), mat.to_vec());
+      assert!(Mat::<u8>::load(&path).is_err());
+      fs::remove_file(&path).unwrap();
+    }
+  }
+
+  #[test]
+  fn geometry() {
+    let rotated = RotatedRect::new(Point2f::new(1.5, 2.5), Size2f::new(3.0, 4.0), 30.0);
+    for ext in &FORMATS {
+      round_trip("geometry", ext, |fs| {
+        fs.write("point", &Point::new(-1, 2)).unwrap();
+        fs.write("point2f", &Point2f::new(0.5, -0.25)).unwrap();
+        fs.write("point2d", &Point2d::new(0.1, 1e10)).unwrap();
+        fs.write("point3f", &Point3f::new(1.0, 2.0, 3.0)).unwrap();
+        fs.write("size", &Size::new(640, 480)).unwrap();
+        fs.write("size2f", &Size2f::new(0.5, 1.5)).unwrap();
+        fs.write("rect", &Rect::new(1, 2, 3, 4)).unwrap();
+        fs.write("rotated", &rotated).unwrap();
+        fs.write("scalar", &[1.0, 2.0, 3.0, 4.0]).unwrap();
+      }, |fs| {
+        assert_eq!(fs.read::<Point>("point").unwrap(), Point::new(-1, 2));
+        assert_eq!(fs.read::<Point2f>("point2f").unwrap(), Point2f::new(0.5, -0.25));
+        assert_eq!(fs.read::<Point2d>("point2d").unwrap(), Point2d::new(0.1, 1e10));
+        assert_eq!(fs.read::<Point3f>("point3f").unwrap(), Point3f::new(1.0, 2.0, 3.0));
+        assert_eq!(fs.read::<Size>("size").unwrap(), Size::new(640, 480));
+        assert_eq!(fs.read::<Size2f>("size2f").unwrap(), Size2f::new(0.5, 1.5));
+        assert_eq!(fs.read::<Rect>("rect").unwrap(), Rect::new(1, 2, 3, 4));
+        assert_eq!(fs.read::<RotatedRect>("rotated").unwrap(), rotated);
+        assert_eq!(fs.read::<[f64;4]>("scalar").unwrap(), [1.0, 2.0, 3.0, 4.0]);
+        assert!(fs.read::<Rect>("point").is_err());
+      });
+    }
+  }
+}
@@ -0,0 +1,18 @@
+#include <cstddef>
+#include <cstring>
+#include <exception>
+
+// Every OpenCV version reports a failure through the handler installed with
+// cvRedirectError and then throws. `body` makes the native calls; catching
+// here keeps the exception from unwinding past Rust's `guard`. Only standard
+// exceptions are caught so a Rust panic keeps unwinding to its caller.
+extern "C" int cvrs_guard(void (*body)(void*), void* data, char* message, size_t message_size) {
+  try {
+    body(data);
+    return 0;
+  } catch (const std::exception& e) {
+    std::strncpy(message, e.what(), message_size - 1);
+    message[message_size - 1] = '\0';
+    return 1;
+  }
+}
@@ -0,0 +1,22 @@
+#include <vector>
+#include <opencv2/features2d/features2d.hpp>
+#include "shim.hpp"
+
+// The C API never covered features2d, so this shim is built for every
+// version. Sequences hold cv::KeyPoint as is; src/ffi/types.rs mirrors it.
+static_assert(sizeof(cv::KeyPoint) == 28, "cv::KeyPoint is not 28 bytes as src/ffi/types.rs expects");
+
+extern "C" {
+
+CvSeq* cvrs_fast(const CvArr* image, int threshold, int nonmax_suppression, CvMemStorage* storage) {
+  std::vector<cv::KeyPoint> keypoints;
+  cv::FAST(cv::cvarrToMat(image), keypoints, threshold, nonmax_suppression != 0);
+
+  CvSeq* seq = cvCreateSeq(0, sizeof(CvSeq), sizeof(cv::KeyPoint), storage);
+  if (!keypoints.empty()) {
+    cvSeqPushMulti(seq, &keypoints[0], static_cast<int>(keypoints.size()));
+  }
+  return seq;
+}
+
+}
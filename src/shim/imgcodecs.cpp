@@ -0,0 +1,47 @@
+#include <algorithm>
+#include <vector>
+#include <opencv2/imgcodecs.hpp>
+#include "shim.hpp"
+
+static std::vector<int> params_from(const int* params) {
+  std::vector<int> result;
+  for (; params && params[0] != 0; params += 2) {
+    result.push_back(params[0]);
+    result.push_back(params[1]);
+  }
+  return result;
+}
+
+static IplImage* clone_to_ipl(const cv::Mat& mat) {
+  if (mat.empty()) {
+    return nullptr;
+  }
+  IplImage header = cvIplImage(mat);
+  return cvCloneImage(&header);
+}
+
+extern "C" {
+
+IplImage* cvrs_load_image(const char* filename, int iscolor) {
+  return clone_to_ipl(cv::imread(filename, iscolor));
+}
+
+int cvrs_save_image(const char* filename, const CvArr* image, const int* params) {
+  return cv::imwrite(filename, cv::cvarrToMat(image), params_from(params)) ? 1 : 0;
+}
+
+IplImage* cvrs_decode_image(const CvMat* buf, int iscolor) {
+  return clone_to_ipl(cv::imdecode(cv::cvarrToMat(buf), iscolor));
+}
+
+CvMat* cvrs_encode_image(const char* ext, const CvArr* image, const int* params) {
+  std::vector<uchar> buf;
+  if (!cv::imencode(ext, cv::cvarrToMat(image), buf, params_from(params))) {
+    return nullptr;
+  }
+  CvMat* mat = cvCreateMat(1, static_cast<int>(buf.size()), CV_8UC1);
+  std::copy(buf.begin(), buf.end(), mat->data.ptr);
+  return mat;
+}
+
+}
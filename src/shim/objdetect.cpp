@@ -0,0 +1,32 @@
+#include <memory>
+#include <vector>
+#include <opencv2/objdetect.hpp>
+#include "shim.hpp"
+
+extern "C" {
+
+cv::CascadeClassifier* cvrs_load_cascade(const char* filename) {
+  std::unique_ptr<cv::CascadeClassifier> cascade(new cv::CascadeClassifier());
+  return cascade->load(filename) ? cascade.release() : nullptr;
+}
+
+CvSeq* cvrs_detect_multi_scale(const CvArr* image, cv::CascadeClassifier* cascade, CvMemStorage* storage,
+    double scale_factor, int min_neighbors, int flags, CvSize min_size, CvSize max_size) {
+  std::vector<cv::Rect> objects;
+  cascade->detectMultiScale(cv::cvarrToMat(image), objects, scale_factor, min_neighbors, flags,
+    cv::Size(min_size.width, min_size.height), cv::Size(max_size.width, max_size.height));
+
+  CvSeq* seq = cvCreateSeq(0, sizeof(CvSeq), sizeof(CvRect), storage);
+  for (const cv::Rect& r : objects) {
+    CvRect rect = cvRect(r.x, r.y, r.width, r.height);
+    cvSeqPush(seq, &rect);
+  }
+  return seq;
+}
+
+void cvrs_release_cascade(cv::CascadeClassifier** cascade) {
+  delete *cascade;
+  *cascade = nullptr;
+}
+
+}
@@ -0,0 +1,12 @@
+#ifndef CVRS_SHIM_HPP
+#define CVRS_SHIM_HPP
+
+#include <opencv2/core/core.hpp>
+#include <opencv2/core/core_c.h>
+
+// Like the C API functions they stand in for, the entry points let
+// exceptions escape: every call from Rust runs inside `cvrs_guard`, which
+// catches them after cv::error has reported the failure through the handler
+// installed with cvRedirectError.
+
+#endif
@@ -0,0 +1,74 @@
+#include <memory>
+#include <utility>
+#include <opencv2/videoio.hpp>
+#include "shim.hpp"
+
+// The legacy API hands out an IplImage owned by the capture, so the capture
+// keeps the decoded frame and a header pointing into it.
+struct CvrsCapture {
+  cv::VideoCapture capture;
+  cv::Mat frame;
+  IplImage header;
+};
+
+static CvrsCapture* open_capture(std::unique_ptr<CvrsCapture> capture) {
+  return capture->capture.isOpened() ? capture.release() : nullptr;
+}
+
+extern "C" {
+
+CvrsCapture* cvrs_create_file_capture(const char* filename) {
+  std::unique_ptr<CvrsCapture> capture(new CvrsCapture());
+  capture->capture.open(filename);
+  return open_capture(std::move(capture));
+}
+
+CvrsCapture* cvrs_create_camera_capture(int index) {
+  std::unique_ptr<CvrsCapture> capture(new CvrsCapture());
+  capture->capture.open(index);
+  return open_capture(std::move(capture));
+}
+
+IplImage* cvrs_query_frame(CvrsCapture* capture) {
+  if (!capture->capture.read(capture->frame) || capture->frame.empty()) {
+    return nullptr;
+  }
+  capture->header = cvIplImage(capture->frame);
+  return &capture->header;
+}
+
+void cvrs_release_capture(CvrsCapture** capture) {
+  delete *capture;
+  *capture = nullptr;
+}
+
+double cvrs_get_capture_property(CvrsCapture* capture, int property_id) {
+  return capture->capture.get(property_id);
+}
+
+int cvrs_set_capture_property(CvrsCapture* capture, int property_id, double value) {
+  return capture->capture.set(property_id, value) ? 1 : 0;
+}
+
+cv::VideoWriter* cvrs_create_video_writer(const char* filename, int fourcc, double fps, CvSize frame_size, int is_color) {
+  std::unique_ptr<cv::VideoWriter> writer(new cv::VideoWriter(filename, fourcc, fps,
+    cv::Size(frame_size.width, frame_size.height), is_color != 0));
+  return writer->isOpened() ? writer.release() : nullptr;
+}
+
+// cv::VideoWriter::write reports nothing, so whether the writer is still
+// open is the only status there is to return.
+int cvrs_write_frame(cv::VideoWriter* writer, const IplImage* image) {
+  if (!writer->isOpened()) {
+    return 0;
+  }
+  writer->write(cv::cvarrToMat(image));
+  return writer->isOpened() ? 1 : 0;
+}
+
+void cvrs_release_video_writer(cv::VideoWriter** writer) {
+  delete *writer;
+  *writer = nullptr;
+}
+
+}
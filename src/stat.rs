@@ -0,0 +1,82 @@
+use std::ptr;
+use ffi::core::*;
+use ffi::types::{CvPoint, CvScalar};
+use arithm::{arr, mask_arr};
+use core::{NormType, Point, Scalar};
+use error::{Result, guard};
+use image::{Image, Mask};
+
+impl Image {
+  pub fn sum(&self, mask: Option<&Mask>) -> Result<Scalar> {
+    self.expect_mask(mask, "cvSum")?;
+    match mask {
+      None => Ok(guard(|| unsafe { cvSum(arr(self)) })?.val),
+      Some(mask) => {
+        let mean = self.mean(Some(mask))?;
+        let count = guard(|| unsafe { cvCountNonZero(arr(mask)) })? as f64;
+        Ok([mean[0] * count, mean[1] * count, mean[2] * count, mean[3] * count])
+      },
+    }
+  }
+
+  pub fn mean(&self, mask: Option<&Mask>) -> Result<Scalar> {
+    self.expect_mask(mask, "cvAvg")?;
+    Ok(guard(|| unsafe { cvAvg(arr(self), mask_arr(mask)) })?.val)
+  }
+
+  pub fn mean_std_dev(&self, mask: Option<&Mask>) -> Result<(Scalar, Scalar)> {
+    self.expect_mask(mask, "cvAvgSdv")?;
+    let mut mean = CvScalar { val: [0.0;4] };
+    let mut std_dev = CvScalar { val: [0.0;4] };
+    guard(|| unsafe { cvAvgSdv(arr(self), &mut mean, &mut std_dev, mask_arr(mask)) })?;
+    Ok((mean.val, std_dev.val))
+  }
+
+  pub fn min_max_loc(&self, mask: Option<&Mask>) -> Result<(f64, f64, Point, Point)> {
+    self.expect_single_channel("cvMinMaxLoc")?;
+    self.expect_mask(mask, "cvMinMaxLoc")?;
+    let (mut min, mut max) = (0.0, 0.0);
+    let mut min_loc = CvPoint { x: 0, y: 0 };
+    let mut max_loc = CvPoint { x: 0, y: 0 };
+    guard(|| unsafe { cvMinMaxLoc(arr(self), &mut min, &mut max, &mut min_loc, &mut max_loc, mask_arr(mask)) })?;
+    Ok((min, max, Point::new(min_loc.x, min_loc.y), Point::new(max_loc.x, max_loc.y)))
+  }
+
+  pub fn count_non_zero(&self, mask: Option<&Mask>) -> Result<u32> {
+    self.expect_single_channel("cvCountNonZero")?;
+    self.expect_mask(mask, "cvCountNonZero")?;
+    let count = match mask {
+      None => guard(|| unsafe { cvCountNonZero(arr(self)) })?,
+      Some(mask) => {
+        // cvCopy ignores the mask when a channel of interest is set, so mask a plain copy of that channel.
+        let plane;
+        let src = match self.coi() {
+          Some(index) => {
+            let mut whole = self.clone();
+            whole.set_coi(None)?;
+            plane = whole.extract_channel(index)?;
+            &plane
+          },
+          None => self,
+        };
+        let masked = Image::new(&self.size(), self.depth()?, 1)?;
+        guard(|| unsafe {
+          cvCopy(arr(src), arr(&masked), arr(mask));
+          cvCountNonZero(arr(&masked))
+        })?
+      },
+    };
+    Ok(count as u32)
+  }
+
+  pub fn norm(&self, norm_type: NormType, mask: Option<&Mask>) -> Result<f64> {
+    self.expect_mask(mask, "cvNorm")?;
+    guard(|| unsafe { cvNorm(arr(self), ptr::null(), norm_type as i32, mask_arr(mask)) })
+  }
+
+  pub fn norm_diff(&self, other: &Image, norm_type: NormType, mask: Option<&Mask>) -> Result<f64> {
+    self.expect_same_shape(other, "cvNorm")?;
+    self.expect_mask(mask, "cvNorm")?;
+    guard(|| unsafe { cvNorm(arr(self), arr(other), norm_type as i32, mask_arr(mask)) })
+  }
+}
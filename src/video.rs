@@ -1,36 +1,53 @@
-use std::mem;
+use std::marker::PhantomData;
+use std::ops::Deref;
 use ffi::videoio::*;
 use ffi::types::{CvCapture, CvSize, CvVideoWriter};
 use core::{Size, as_c_str};
+use error::{Error, Result, guard};
 use image::Image;
 
 use std::path::Path;
 
+pub struct FrameRef<'cap> {
+  image: Image,
+  _capture: PhantomData<&'cap mut Capture>,
+}
+
+impl<'cap> FrameRef<'cap> {
+  pub fn to_owned(&self) -> Image {
+    self.image.clone()
+  }
+}
+
+impl<'cap> Deref for FrameRef<'cap> {
+  type Target = Image;
+  fn deref(&self) -> &Image {
+    &self.image
+  }
+}
+
 pub struct Frames<'a> {
-  capture: &'a Capture,
+  capture: &'a mut Capture,
 }
 
 impl<'a> Frames<'a> {
-  pub fn at(&self, index: u32) -> Option<Image> {
-    unsafe {
-      cvSetCaptureProperty(self.capture.raw, CV_CAP_PROP_POS_FRAMES, index as f64);
-      match cvQueryFrame(self.capture.raw) {
-        p if !p.is_null() => Some(Image { raw: p, is_owned: false }),
-        _ => None,
-      }
-    }
+  pub fn at(&mut self, index: u32) -> Result<Option<FrameRef<'_>>> {
+    self.capture.frame_at(index)
   }
 
-  pub fn count(&self) -> u32 {
-    unsafe { cvGetCaptureProperty(self.capture.raw, CV_CAP_PROP_FRAME_COUNT) as u32 - 2 } // ??
+  pub fn count(&self) -> Result<Option<u32>> {
+    self.capture.frame_count()
   }
 }
 
 impl<'a> Iterator for Frames<'a> {
-  type Item = Image;
-  fn next(&mut self) -> Option<Image> {
-    let index = unsafe { cvGetCaptureProperty(self.capture.raw, CV_CAP_PROP_POS_FRAMES) as u32 };
-    self.at(index + 1)
+  type Item = Result<Image>;
+  fn next(&mut self) -> Option<Result<Image>> {
+    let index = match guard(|| unsafe { cvGetCaptureProperty(self.capture.raw, CV_CAP_PROP_POS_FRAMES) }) {
+      Ok(index) => index as u32,
+      Err(e) => return Some(Err(e)),
+    };
+    self.at(index + 1).map(|frame| frame.map(|frame| frame.to_owned())).transpose()
   }
 }
 
@@ -39,39 +56,48 @@ pub struct Capture {
 }
 
 impl Capture {
-  pub fn from_file(path: &Path) -> Result<Capture, String> {
+  pub fn from_file(path: &Path) -> Result<Capture> {
     let path_c_str = as_c_str(path);
-    unsafe {
-      match cvCreateFileCapture(path_c_str.as_ptr()) {
-        p if !p.is_null() => Ok(Capture { raw: p }),
-        _ => Err(String::from_utf8_lossy(path_c_str.to_bytes()).into_owned()),
-      }
+    match guard(|| unsafe { cvCreateFileCapture(path_c_str.as_ptr()) })? {
+      p if !p.is_null() => Ok(Capture { raw: p }),
+      _ => Err(Error::from_path(path)),
+    }
+  }
+
+  pub fn from_camera(index: i32) -> Result<Capture> {
+    match guard(|| unsafe { cvCreateCameraCapture(index as i32) })? {
+      c if !c.is_null() => Ok(Capture { raw: c }),
+      _ => Err(Error::Io(format!("camera {}", index))),
     }
   }
 
-  pub fn from_camera(index: i32) -> Result<Capture, String> {
-    unsafe {
-      match cvCreateCameraCapture(index as i32) {
-        c if !c.is_null() => {
-          Ok(Capture {raw: c})
-        },
-        _ => {
-          let err_message = format!(
-            "Camera failed to properly initialize! Maybe you're using the wrong camera index ({}). {}",
-            index,
-            "Use `from_camera(0)` to autodetect the camera index."
-          );
-          Err(err_message)
-        }
-      }
+  pub fn query_frame(&mut self) -> Result<Option<FrameRef<'_>>> {
+    match guard(|| unsafe { cvQueryFrame(self.raw) })? {
+      p if !p.is_null() => Ok(Some(FrameRef { image: unsafe { Image::borrowed(p) }, _capture: PhantomData })),
+      _ => Ok(None),
     }
   }
 
-  pub fn frames(&self) -> Frames {
+  pub fn frame_at(&mut self, index: u32) -> Result<Option<FrameRef<'_>>> {
+    guard(|| unsafe { cvSetCaptureProperty(self.raw, CV_CAP_PROP_POS_FRAMES, index as f64) })?;
+    self.query_frame()
+  }
+
+  // Cameras and streams report 0 or -1, as do containers without a frame index.
+  pub fn frame_count(&self) -> Result<Option<u32>> {
+    match guard(|| unsafe { cvGetCaptureProperty(self.raw, CV_CAP_PROP_FRAME_COUNT) })? {
+      count if count >= 1.0 => Ok(Some(count as u32)),
+      _ => Ok(None),
+    }
+  }
+
+  pub fn frames(&mut self) -> Frames<'_> {
     Frames { capture: self }
   }
 }
 
+unsafe impl Send for Capture {}
+
 impl Drop for Capture {
   fn drop(&mut self) {
     unsafe { cvReleaseCapture(&self.raw); }
@@ -83,25 +109,46 @@ pub struct Writer {
 }
 
 impl Writer {
-  pub fn open(path: &Path, fourcc: &[char;4], fps: f64, frame: &Size, is_color: bool) -> Result<Writer, String> {
-    let fourcc = unsafe { mem::transmute::<_, i32>([fourcc[0] as u8, fourcc[1] as u8, fourcc[2] as u8, fourcc[3] as u8]) };
+  pub fn open(path: &Path, fourcc: &[char;4], fps: f64, frame: &Size, is_color: bool) -> Result<Writer> {
+    if !(fps > 0.0) {
+      return Err(Error::BadArgument(format!("fps must be positive, got {}", fps)));
+    }
+    if frame.width <= 0 || frame.height <= 0 {
+      return Err(Error::BadArgument(format!("invalid frame size {}x{}", frame.width, frame.height)));
+    }
+
+    let codec = fourcc.iter().collect::<String>();
+    if !codec.is_ascii() {
+      return Err(Error::BadArgument(format!("fourcc codes are ASCII, got {:?}", codec)));
+    }
+    // CV_FOURCC packs the first character into the low byte.
+    let fourcc = u32::from_le_bytes([fourcc[0] as u8, fourcc[1] as u8, fourcc[2] as u8, fourcc[3] as u8]) as i32;
     let is_color = if is_color { 1 } else { 0 };
 
     let path_c_str = as_c_str(path);
-    unsafe {
-      let frame_size = CvSize { width: frame.width as i32, height: frame.height as i32 };
-      match cvCreateVideoWriter(path_c_str.as_ptr(), fourcc, fps as f64, frame_size, is_color as i32) {
-        p if !p.is_null() => Ok(Writer { raw: p }),
-        _ => Err(String::from_utf8_lossy(path_c_str.to_bytes()).into_owned()),
-      }
+    let frame_size = CvSize { width: frame.width as i32, height: frame.height as i32 };
+    match guard(|| unsafe { cvCreateVideoWriter(path_c_str.as_ptr(), fourcc, fps as f64, frame_size, is_color as i32) })? {
+      p if !p.is_null() => Ok(Writer { raw: p }),
+      _ => Err(Error::from_output_path(path, format!("{} ({})", path.display(), codec))),
     }
   }
 
-  pub fn write(&self, image: &Image) -> bool {
-    unsafe { cvWriteFrame(self.raw, image.raw) != 0 }
+  pub fn write(&self, image: &Image) -> Result<()> {
+    match guard(|| unsafe { cvWriteFrame(self.raw, image.as_raw()) })? {
+      0 => Err(Error::Native {
+        status: -2, // CV_StsError
+        func: "cvWriteFrame".to_string(),
+        message: format!("the writer rejected a {}x{} frame", image.width(), image.height()),
+        file: String::new(),
+        line: 0,
+      }),
+      _ => Ok(()),
+    }
   }
 }
 
+unsafe impl Send for Writer {}
+
 impl Drop for Writer {
   fn drop(&mut self) {
     unsafe { cvReleaseVideoWriter(&self.raw); }
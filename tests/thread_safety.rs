@@ -0,0 +1,123 @@
+extern crate opencv;
+
+use std::sync::Arc;
+use std::thread;
+use opencv::core::{MemStorage, Size};
+use opencv::image::{Depth, Image};
+use opencv::mat::Mat;
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn owned_types_are_thread_safe() {
+  assert_send::<Image>();
+  assert_sync::<Image>();
+  assert_send::<Mat<f64>>();
+  assert_sync::<Mat<f64>>();
+  assert_send::<MemStorage>();
+  #[cfg(feature = "objdetect")]
+  {
+    assert_send::<opencv::objdetect::CascadeClassifier>();
+    assert_send::<opencv::objdetect::CascadePool>();
+    assert_sync::<opencv::objdetect::CascadePool>();
+  }
+  #[cfg(feature = "video")]
+  {
+    assert_send::<opencv::video::Capture>();
+    assert_send::<opencv::video::Writer>();
+  }
+}
+
+#[test]
+fn image_moves_to_another_thread() {
+  let image = Image::new(&Size::new(64, 48), Depth::U8, 3).unwrap();
+  let shape = thread::spawn(move || (image.width(), image.height(), image.channels())).join().unwrap();
+  assert_eq!(shape, (64, 48, 3));
+}
+
+#[test]
+fn image_is_read_from_several_threads() {
+  let image = Image::new(&Size::new(64, 48), Depth::U8, 1).unwrap().add_scalar(&[7.0;4], None).unwrap();
+  let image = Arc::new(image);
+  let handles = (0..4).map(|_| {
+    let image = image.clone();
+    thread::spawn(move || image.sum(None).unwrap()[0])
+  }).collect::<Vec<_>>();
+  for handle in handles {
+    assert_eq!(handle.join().unwrap(), 7.0 * 64.0 * 48.0);
+  }
+}
+
+#[test]
+fn mat_moves_to_another_thread() {
+  let mat = Mat::from_slice(2, 2, &[1.0, 2.0, 3.0, 4.0]).unwrap();
+  let det = thread::spawn(move || mat.det().unwrap()).join().unwrap();
+  assert!((det + 2.0).abs() < 1e-9);
+}
+
+#[cfg(feature = "objdetect")]
+mod cascades {
+  use std::env;
+  use std::panic::{self, AssertUnwindSafe};
+  use std::path::PathBuf;
+  use std::sync::Arc;
+  use std::thread;
+  use opencv::core::Size;
+  use opencv::image::{Depth, Image};
+  use opencv::objdetect::{CascadeClassifier, CascadePool};
+
+  const CASCADE: &str = "haarcascades/haarcascade_frontalface_alt.xml";
+
+  // These need a trained cascade, so they only run with `cargo test -- --ignored`.
+  // Set OPENCV_CASCADE to use a cascade outside the usual install locations.
+  fn cascade_path() -> PathBuf {
+    if let Some(path) = env::var_os("OPENCV_CASCADE") {
+      let path = PathBuf::from(path);
+      assert!(path.is_file(), "OPENCV_CASCADE is set to {}, which is not a file", path.display());
+      return path;
+    }
+    let prefixes = ["/usr/share/opencv4", "/usr/share/opencv", "/usr/local/share/opencv4", "/usr/local/share/OpenCV", "/usr/share/OpenCV"];
+    prefixes.iter().map(|p| PathBuf::from(p).join(CASCADE)).find(|p| p.is_file())
+      .expect("no cascade found; set OPENCV_CASCADE")
+  }
+
+  fn blank() -> Image {
+    Image::new(&Size::new(160, 120), Depth::U8, 1).unwrap()
+  }
+
+  #[test]
+  #[ignore]
+  fn classifier_moves_to_another_thread() {
+    let classifier = CascadeClassifier::load(&cascade_path()).unwrap();
+    let found = thread::spawn(move || {
+      classifier.detect_multi_scale(&blank(), 1.1, 3, 0, Size::new(20, 20), Size::new(0, 0)).unwrap()
+    }).join().unwrap();
+    assert!(found.is_empty());
+  }
+
+  #[test]
+  #[ignore]
+  fn pool_detects_from_several_threads() {
+    let pool = Arc::new(CascadePool::load(&cascade_path()).unwrap());
+    let image = Arc::new(blank());
+    let handles = (0..8).map(|_| {
+      let (pool, image) = (pool.clone(), image.clone());
+      thread::spawn(move || {
+        (0..10).map(|_| pool.detect_multi_scale(&image, 1.1, 3, 0, Size::new(20, 20), Size::new(0, 0)).unwrap().len()).sum::<usize>()
+      })
+    }).collect::<Vec<_>>();
+    for handle in handles {
+      assert_eq!(handle.join().unwrap(), 0);
+    }
+  }
+
+  #[test]
+  #[ignore]
+  fn pool_survives_a_panicking_caller() {
+    let pool = CascadePool::load(&cascade_path()).unwrap();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| pool.with(|_| -> () { panic!("caller failed") })));
+    assert!(result.is_err());
+    assert!(pool.detect_multi_scale(&blank(), 1.1, 3, 0, Size::new(20, 20), Size::new(0, 0)).unwrap().is_empty());
+  }
+}